@@ -0,0 +1,53 @@
+//! Render kitty dna to SVG.
+//!
+//! Usage: `kitty-render <DNA_HEX>...` writes a single kitty to stdout, or
+//! `kitty-render --out-dir <DIR> <DNA_HEX>...` writes `<DIR>/<DNA_HEX>.svg` for each kitty.
+
+use std::{env, fs, io::Write, path::PathBuf, process};
+
+use pallet_kitties_renderer::{parse_hex, render_svg};
+
+const USAGE: &str = "usage: kitty-render [--out-dir <DIR>] <DNA_HEX>...";
+
+fn main() {
+	if let Err(e) = run(env::args().skip(1).collect()) {
+		eprintln!("kitty-render: {}", e);
+		process::exit(1);
+	}
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+	let mut out_dir = None;
+	let mut dnas = Vec::new();
+
+	let mut args = args.into_iter();
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"-o" | "--out-dir" =>
+				out_dir = Some(PathBuf::from(args.next().ok_or_else(|| USAGE.to_string())?)),
+			"-h" | "--help" => {
+				println!("{}", USAGE);
+				return Ok(())
+			},
+			_ => dnas.push(arg),
+		}
+	}
+
+	match (out_dir, dnas.as_slice()) {
+		(_, []) => Err(USAGE.to_string()),
+		(None, [dna]) => {
+			let svg = render_svg(&parse_hex(dna).map_err(|e| e.to_string())?);
+			std::io::stdout().write_all(svg.as_bytes()).map_err(|e| e.to_string())
+		},
+		(None, _) => Err("--out-dir is required for more than one kitty".to_string()),
+		(Some(dir), dnas) => {
+			fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+			for dna in dnas {
+				let dna = parse_hex(dna).map_err(|e| e.to_string())?;
+				let path = dir.join(format!("{}.svg", hex::encode(dna)));
+				fs::write(&path, render_svg(&dna)).map_err(|e| format!("{}: {}", path.display(), e))?;
+			}
+			Ok(())
+		},
+	}
+}
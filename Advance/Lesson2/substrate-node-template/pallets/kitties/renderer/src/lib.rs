@@ -0,0 +1,241 @@
+//! Deterministic SVG artwork for kitty dna.
+//!
+//! Wallets and indexers render the same `Kitty([u8; 16])` to byte-identical SVG, so the output
+//! only depends on the dna and this crate version. Any change to the artwork must update the
+//! golden files under `tests/golden`.
+
+use std::fmt::{self, Write};
+
+use codec::Decode;
+
+/// Bytes of kitty dna.
+pub const DNA_LEN: usize = 16;
+
+/// Width and height of the rendered image.
+pub const SIZE: u32 = 256;
+
+/// Kitty dna, SCALE-encoded the same as `pallet_kitties::Kitty`.
+pub type Dna = [u8; DNA_LEN];
+
+/// Errors reading kitty dna.
+#[derive(Debug)]
+pub enum Error {
+	/// Input is not valid hex.
+	Hex(hex::FromHexError),
+	/// Input is not a SCALE-encoded kitty.
+	Codec(codec::Error),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Hex(e) => write!(f, "invalid dna hex: {}", e),
+			Error::Codec(e) => write!(f, "invalid kitty encoding: {}", e),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+/// Decode SCALE-encoded kitty dna, e.g. a `Kitties` storage value. Trailing bytes are rejected.
+pub fn decode_dna(mut input: &[u8]) -> Result<Dna, Error> {
+	let dna = Dna::decode(&mut input).map_err(Error::Codec)?;
+	if !input.is_empty() {
+		return Err(Error::Codec("trailing bytes after kitty dna".into()))
+	}
+	Ok(dna)
+}
+
+/// Decode kitty dna from hex, with or without `0x` prefix.
+pub fn parse_hex(input: &str) -> Result<Dna, Error> {
+	let input = input.trim();
+	let input = input.strip_prefix("0x").unwrap_or(input);
+	let bytes = hex::decode(input).map_err(Error::Hex)?;
+	decode_dna(&bytes)
+}
+
+/// Ear shapes picked by dna.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ears {
+	Pointy,
+	Round,
+	Folded,
+}
+
+/// Mouth shapes picked by dna.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mouth {
+	Smile,
+	Flat,
+	Open,
+}
+
+/// Visible traits decoded from dna, one or more bytes each.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Traits {
+	/// Bytes 0..3.
+	pub body: Rgb,
+	/// Bytes 3..6.
+	pub belly: Rgb,
+	/// Bytes 6..9.
+	pub eyes: Rgb,
+	/// Byte 9.
+	pub ears: Ears,
+	/// Byte 10, eye radius in pixels.
+	pub eye_size: u32,
+	/// Byte 11, stripes on the forehead.
+	pub stripes: u32,
+	/// Byte 12.
+	pub mouth: Mouth,
+	/// Byte 13, whiskers on each side.
+	pub whiskers: u32,
+	/// Byte 14, background hue in degrees.
+	pub background_hue: u32,
+	/// Byte 15, cheek spots.
+	pub spots: bool,
+}
+
+/// Color as red, green, blue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl fmt::Display for Rgb {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+	}
+}
+
+impl Rgb {
+	/// Darker shade for outlines and stripes.
+	fn shade(self) -> Rgb {
+		Rgb(self.0 / 2, self.1 / 2, self.2 / 2)
+	}
+}
+
+impl Traits {
+	pub fn from_dna(dna: &Dna) -> Self {
+		Traits {
+			body: Rgb(dna[0], dna[1], dna[2]),
+			belly: Rgb(dna[3], dna[4], dna[5]),
+			eyes: Rgb(dna[6], dna[7], dna[8]),
+			ears: match dna[9] % 3 {
+				0 => Ears::Pointy,
+				1 => Ears::Round,
+				_ => Ears::Folded,
+			},
+			eye_size: 8 + u32::from(dna[10] % 8),
+			stripes: u32::from(dna[11] % 5),
+			mouth: match dna[12] % 3 {
+				0 => Mouth::Smile,
+				1 => Mouth::Flat,
+				_ => Mouth::Open,
+			},
+			whiskers: 1 + u32::from(dna[13] % 3),
+			background_hue: u32::from(dna[14]) * 360 / 256,
+			spots: dna[15] & 1 == 1,
+		}
+	}
+}
+
+/// Render kitty dna to a standalone SVG document.
+pub fn render_svg(dna: &Dna) -> String {
+	let traits = Traits::from_dna(dna);
+	let outline = traits.body.shade();
+	let mut svg = String::new();
+
+	// Writing to a `String` never fails.
+	let _ = writeln!(
+		svg,
+		r#"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">"#,
+		size = SIZE
+	);
+	let _ = writeln!(svg, "<title>kitty 0x{}</title>", hex::encode(dna));
+	let _ = writeln!(
+		svg,
+		r#"<rect width="{size}" height="{size}" fill="hsl({}, 60%, 88%)"/>"#,
+		traits.background_hue,
+		size = SIZE
+	);
+
+	// Ears behind the head.
+	let ears = match traits.ears {
+		Ears::Pointy => ["M 58 110 L 70 28 L 118 72 Z", "M 198 110 L 186 28 L 138 72 Z"],
+		Ears::Round => ["M 60 100 Q 56 34 116 70 Z", "M 196 100 Q 200 34 140 70 Z"],
+		Ears::Folded => ["M 58 96 L 76 52 L 112 64 L 88 80 Z", "M 198 96 L 180 52 L 144 64 L 168 80 Z"],
+	};
+	for ear in ears {
+		let _ = writeln!(
+			svg,
+			r#"<path d="{}" fill="{}" stroke="{}" stroke-width="3"/>"#,
+			ear, traits.body, outline
+		);
+	}
+
+	// Head and belly patch.
+	let _ = writeln!(
+		svg,
+		r#"<ellipse cx="128" cy="140" rx="84" ry="74" fill="{}" stroke="{}" stroke-width="3"/>"#,
+		traits.body, outline
+	);
+	let _ = writeln!(svg, r#"<ellipse cx="128" cy="176" rx="44" ry="30" fill="{}"/>"#, traits.belly);
+
+	// Forehead stripes, centered.
+	for i in 0..traits.stripes {
+		let x = 128 + (2 * i as i32 - traits.stripes as i32 + 1) * 12;
+		let _ = writeln!(
+			svg,
+			r#"<path d="M {} 70 L {} 96" stroke="{}" stroke-width="5" stroke-linecap="round"/>"#,
+			x,
+			x,
+			outline
+		);
+	}
+
+	// Eyes with pupils.
+	for cx in [96, 160] {
+		let _ = writeln!(
+			svg,
+			r#"<circle cx="{}" cy="128" r="{}" fill="{}" stroke="{}" stroke-width="2"/>"#,
+			cx, traits.eye_size, traits.eyes, outline
+		);
+		let _ = writeln!(svg, r##"<circle cx="{}" cy="128" r="{}" fill="#000000"/>"##, cx, traits.eye_size / 3);
+	}
+
+	// Cheek spots.
+	if traits.spots {
+		for cx in [76, 180] {
+			let _ = writeln!(svg, r#"<circle cx="{}" cy="164" r="9" fill="{}" opacity="0.6"/>"#, cx, outline);
+		}
+	}
+
+	// Nose and mouth.
+	let _ = writeln!(svg, r##"<path d="M 120 152 L 136 152 L 128 162 Z" fill="#d46a7e"/>"##);
+	let mouth = match traits.mouth {
+		Mouth::Smile => "M 112 170 Q 128 184 144 170",
+		Mouth::Flat => "M 114 172 L 142 172",
+		Mouth::Open => "M 116 168 Q 128 192 140 168 Z",
+	};
+	let _ = writeln!(
+		svg,
+		r#"<path d="{}" fill="{}" stroke="{}" stroke-width="3" stroke-linecap="round"/>"#,
+		mouth,
+		if traits.mouth == Mouth::Open { "#7a2333" } else { "none" },
+		outline
+	);
+
+	// Whiskers on both sides.
+	for i in 0..traits.whiskers {
+		let y = 160 + i * 8;
+		let _ = writeln!(
+			svg,
+			r#"<path d="M 100 {y} L 44 {} M 156 {y} L 212 {}" stroke="{}" stroke-width="2"/>"#,
+			y - 6 + i * 6,
+			y - 6 + i * 6,
+			outline,
+			y = y
+		);
+	}
+
+	svg.push_str("</svg>\n");
+	svg
+}
@@ -0,0 +1,52 @@
+//! Rendered artwork must not change between versions without updating the golden files.
+//!
+//! Regenerate with `UPDATE_GOLDEN=1 cargo test`.
+
+use std::{fs, path::PathBuf};
+
+use pallet_kitties_renderer::{parse_hex, render_svg};
+
+const KITTIES: &[&str] = &[
+	"00000000000000000000000000000000",
+	"ffffffffffffffffffffffffffffffff",
+	"0x1f8e3a55c2907d4b16e0a9f3384cd712",
+	"0xa3c15e7700d2486b9e0f21c4b7358a6d",
+];
+
+fn golden_path(dna: &str) -> PathBuf {
+	PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+		.join("tests/golden")
+		.join(format!("{}.svg", dna.trim_start_matches("0x")))
+}
+
+#[test]
+fn render_matches_golden_files() {
+	let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+
+	for dna in KITTIES {
+		let svg = render_svg(&parse_hex(dna).unwrap());
+		let path = golden_path(dna);
+
+		if update {
+			fs::write(&path, &svg).unwrap();
+			continue
+		}
+
+		let golden = fs::read_to_string(&path)
+			.unwrap_or_else(|e| panic!("{}: {}, run with UPDATE_GOLDEN=1", path.display(), e));
+		assert_eq!(svg, golden, "artwork changed for {}", dna);
+	}
+}
+
+#[test]
+fn render_is_deterministic() {
+	let dna = parse_hex(KITTIES[2]).unwrap();
+	assert_eq!(render_svg(&dna), render_svg(&dna));
+}
+
+#[test]
+fn parse_hex_rejects_bad_input() {
+	assert!(parse_hex("0x00").is_err());
+	assert!(parse_hex("zz").is_err());
+	assert!(parse_hex(&"00".repeat(17)).is_err());
+}
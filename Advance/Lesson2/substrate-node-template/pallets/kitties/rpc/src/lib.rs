@@ -0,0 +1,149 @@
+//! JSON RPC for querying kitties, served by the node on top of `KittiesApi`.
+//!
+//! Add to the node rpc module with `Kitties::new(client.clone()).into_rpc()`.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::{Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use pallet_kitties_runtime_api::{KittyDetails, Page};
+use serde::{de::DeserializeOwned, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_kitties_runtime_api::KittiesApi as KittiesRuntimeApi;
+
+/// Error code for runtime api call failures.
+const RUNTIME_ERROR: i32 = 1;
+
+#[rpc(client, server)]
+pub trait KittiesApi<BlockHash, AccountId, KittyIndex, Balance> {
+	#[method(name = "kitties_kittiesOf")]
+	fn kitties_of(
+		&self,
+		owner: AccountId,
+		start: u32,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Page<KittyIndex, u32>>;
+
+	#[method(name = "kitties_allKitties")]
+	fn all_kitties(
+		&self,
+		start: KittyIndex,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Page<(KittyIndex, KittyDetails<AccountId, Balance>), KittyIndex>>;
+
+	#[method(name = "kitties_onSale")]
+	fn on_sale(
+		&self,
+		start: KittyIndex,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Page<(KittyIndex, Balance), KittyIndex>>;
+
+	#[method(name = "kitties_kittyDetails")]
+	fn kitty_details(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<KittyDetails<AccountId, Balance>>>;
+}
+
+/// Kitties RPC methods.
+pub struct Kitties<C, Block> {
+	client: Arc<C>,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> Kitties<C, Block> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block> Kitties<C, Block>
+where
+	Block: BlockT,
+	C: HeaderBackend<Block>,
+{
+	fn block_id(&self, at: Option<Block::Hash>) -> BlockId<Block> {
+		// If the block hash is not supplied assume the best block.
+		BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash))
+	}
+}
+
+impl<C, Block, AccountId, KittyIndex, Balance>
+	KittiesApiServer<<Block as BlockT>::Hash, AccountId, KittyIndex, Balance> for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: KittiesRuntimeApi<Block, AccountId, KittyIndex, Balance>,
+	AccountId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	KittyIndex: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	Balance: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+	fn kitties_of(
+		&self,
+		owner: AccountId,
+		start: u32,
+		limit: u32,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Page<KittyIndex, u32>> {
+		self.client
+			.runtime_api()
+			.kitties_of(&self.block_id(at), owner, start, limit)
+			.map_err(runtime_error_into_rpc_err)
+	}
+
+	fn all_kitties(
+		&self,
+		start: KittyIndex,
+		limit: u32,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Page<(KittyIndex, KittyDetails<AccountId, Balance>), KittyIndex>> {
+		self.client
+			.runtime_api()
+			.all_kitties(&self.block_id(at), start, limit)
+			.map_err(runtime_error_into_rpc_err)
+	}
+
+	fn on_sale(
+		&self,
+		start: KittyIndex,
+		limit: u32,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Page<(KittyIndex, Balance), KittyIndex>> {
+		self.client
+			.runtime_api()
+			.on_sale(&self.block_id(at), start, limit)
+			.map_err(runtime_error_into_rpc_err)
+	}
+
+	fn kitty_details(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<KittyDetails<AccountId, Balance>>> {
+		self.client
+			.runtime_api()
+			.kitty_details(&self.block_id(at), kitty_id)
+			.map_err(runtime_error_into_rpc_err)
+	}
+}
+
+/// Converts a runtime api error into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		RUNTIME_ERROR,
+		"Runtime error",
+		Some(format!("{:?}", err)),
+	))
+	.into()
+}
@@ -0,0 +1,29 @@
+//! Runtime API for querying kitties, paginated by kitty id.
+//!
+//! Each page checks at most `MAX_PAGE_LIMIT` ids and returns the cursor of the next page.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+pub use pallet_kitties::{Kitty, KittyDetails, Page, MAX_PAGE_LIMIT};
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<AccountId, KittyIndex, Balance> where
+		AccountId: Codec,
+		KittyIndex: Codec,
+		Balance: Codec,
+	{
+		/// Up to `limit` kitty ids owned by an account, from position `start` in its owned list.
+		fn kitties_of(owner: AccountId, start: u32, limit: u32) -> Page<KittyIndex, u32>;
+
+		/// Kitties among `limit` ids from id `start`, burned ids are skipped.
+		fn all_kitties(start: KittyIndex, limit: u32) -> Page<(KittyIndex, KittyDetails<AccountId, Balance>), KittyIndex>;
+
+		/// Kitties for sale among `limit` ids from id `start`, with prices.
+		fn on_sale(start: KittyIndex, limit: u32) -> Page<(KittyIndex, Balance), KittyIndex>;
+
+		/// Dna, owner, creator and price of a kitty.
+		fn kitty_details(kitty_id: KittyIndex) -> Option<KittyDetails<AccountId, Balance>>;
+	}
+}
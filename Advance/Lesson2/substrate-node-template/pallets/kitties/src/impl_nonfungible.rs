@@ -0,0 +1,94 @@
+//! Implementations for `nonfungible` traits, kitties as a single collection.
+
+use super::*;
+use frame_support::{
+    dispatch::DispatchResult,
+    ensure,
+    BoundedVec,
+    traits::tokens::nonfungible::{Inspect, Mutate, Transfer},
+};
+use sp_std::vec::Vec;
+
+impl<T: Config<I>, I: 'static> Inspect<T::AccountId> for Pallet<T, I>
+{
+    type ItemId = T::KittyIndex;
+
+    fn owner(item: &Self::ItemId) -> Option<T::AccountId>
+    {
+        Self::kitty_owned_by(item)
+    }
+
+    /* Kitty dna under `dna` key, otherwise attributes set by owner */
+    fn attribute(item: &Self::ItemId, key: &[u8]) -> Option<Vec<u8>>
+    {
+        match key
+        {
+            b"dna" => Self::kitties(item).map(|kitty| kitty.0.to_vec()),
+            _ => {
+                let key: BoundedVec<u8, T::StringLimit> = key.to_vec().try_into().ok()?;
+                Self::kitty_attribute(item, key).map(|attribute| attribute.value.into_inner())
+            },
+        }
+    }
+
+    /* Kitties waiting for dna reveal, bred from or pledged for a loan can not be transferred */
+    fn can_transfer(item: &Self::ItemId) -> bool
+    {
+        Self::kitties(item).is_some() && Self::ensure_unlocked(*item).is_ok()
+    }
+}
+
+impl<T: Config<I>, I: 'static> Transfer<T::AccountId> for Pallet<T, I>
+{
+    fn transfer(item: &Self::ItemId, destination: &T::AccountId) -> DispatchResult
+    {
+        /* Check kitty exist */
+        let owner = Self::kitty_owned_by(item).ok_or(Error::<T, I>::InvalidKittyId)?;
+        ensure!(Self::kitties(item).is_some(), Error::<T, I>::InvalidKittyId);
+        Self::ensure_unlocked(*item)?;
+
+        /* Transfer kitty with reserve */
+        Self::do_transfer(*item, &owner, destination)?;
+
+        /* Post event */
+        Self::deposit_event(Event::KittyTransferred(owner, destination.clone(), *item));
+
+        Ok(())
+    }
+}
+
+impl<T: Config<I>, I: 'static> Mutate<T::AccountId> for Pallet<T, I>
+{
+    /* Kitty ids are sequential, only next id can be minted */
+    fn mint_into(item: &Self::ItemId, who: &T::AccountId) -> DispatchResult
+    {
+        ensure!(*item == Self::kitty_count(), Error::<T, I>::InvalidKittyId);
+
+        /* Commit kitty with reserve, dna revealed later */
+        let seed = Self::random_value(who);
+        Self::commit(who, seed, None)?;
+
+        Ok(())
+    }
+
+    fn burn(item: &Self::ItemId, maybe_check_owner: Option<&T::AccountId>) -> DispatchResult
+    {
+        /* Check kitty exist */
+        let owner = Self::kitty_owned_by(item).ok_or(Error::<T, I>::InvalidKittyId)?;
+        ensure!(Self::kitties(item).is_some(), Error::<T, I>::InvalidKittyId);
+        Self::ensure_unlocked(*item)?;
+
+        if let Some(check_owner) = maybe_check_owner
+        {
+            ensure!(*check_owner == owner, Error::<T, I>::NotOwner);
+        }
+
+        /* Remove kitty and unreserve */
+        Self::do_burn(*item, &owner)?;
+
+        /* Post event */
+        Self::deposit_event(Event::KittyBurned(owner, *item));
+
+        Ok(())
+    }
+}
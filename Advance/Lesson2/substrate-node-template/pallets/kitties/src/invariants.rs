@@ -0,0 +1,213 @@
+//! Property tests driving random call sequences against the mock runtime.
+
+use crate::mock::{new_test_ext, run_to_block, Balances, Call as TestCall, Origin, Reserved, System, Test};
+use crate::*;
+use frame_support::traits::ReservableCurrency;
+use proptest::prelude::*;
+use sp_runtime::traits::Dispatchable;
+
+const ACCOUNTS: [u64; 4] = [1, 2, 3, 4];
+
+#[derive(Clone, Debug)]
+enum Action
+{
+	Create(u64),
+	Breed(u64, u32, u32),
+	Transfer(u64, u32, u64),
+	Sell(u64, u32, u128),
+	Buy(u64, u32),
+	Burn(u64, u32),
+	MakeOffer(u64, u32, u128, u64),
+	CancelOffer(u64, u32),
+	AcceptOffer(u64, u32, u64),
+	RequestLoan(u64, u32, u128, u128, u64),
+	FundLoan(u64, u32),
+	RepayLoan(u64, u32),
+	ClaimCollateral(u64, u32),
+	ProposeSwap(u64, u32, u32, Option<TopUp<u128>>),
+	AcceptSwap(u64, u32),
+	/* Offers expire and loans pass their deadline */
+	NextBlock,
+}
+
+impl Action
+{
+	/* Signer and runtime call, dispatched like an extrinsic so failed calls are reverted */
+	fn into_call(self) -> Option<(u64, TestCall)>
+	{
+		Some(match self
+		{
+			Action::Create(who) => (who, TestCall::Kitties(Call::create {})),
+			Action::Breed(who, kitty_id_1, kitty_id_2) => (who, TestCall::Kitties(Call::breed { kitty_id_1, kitty_id_2 })),
+			Action::Transfer(who, kitty_id, to) => (who, TestCall::Kitties(Call::transfer { kitty_id, who: to })),
+			Action::Sell(who, kitty_id, price) => (who, TestCall::Kitties(Call::sell { kitty_id, price })),
+			Action::Buy(who, kitty_id) => (who, TestCall::Kitties(Call::buy { kitty_id })),
+			Action::Burn(who, kitty_id) => (who, TestCall::Kitties(Call::burn { kitty_id })),
+			Action::MakeOffer(who, kitty_id, amount, expiry) => (who, TestCall::Kitties(Call::make_offer { kitty_id, amount, expiry })),
+			Action::CancelOffer(who, kitty_id) => (who, TestCall::Kitties(Call::cancel_offer { kitty_id })),
+			Action::AcceptOffer(who, kitty_id, buyer) => (who, TestCall::Kitties(Call::accept_offer { kitty_id, buyer })),
+			Action::RequestLoan(who, kitty_id, amount, repayment, duration) => (who, TestCall::Kitties(Call::request_loan { kitty_id, amount, repayment, duration })),
+			Action::FundLoan(who, kitty_id) => (who, TestCall::Kitties(Call::fund_loan { kitty_id })),
+			Action::RepayLoan(who, kitty_id) => (who, TestCall::Kitties(Call::repay_loan { kitty_id })),
+			Action::ClaimCollateral(who, kitty_id) => (who, TestCall::Kitties(Call::claim_collateral { kitty_id })),
+			Action::ProposeSwap(who, my_kitty, their_kitty, top_up) => (who, TestCall::Kitties(Call::propose_swap { my_kitty, their_kitty, top_up })),
+			Action::AcceptSwap(who, my_kitty) => (who, TestCall::Kitties(Call::accept_swap { my_kitty })),
+			Action::NextBlock => return None,
+		})
+	}
+}
+
+fn account() -> impl Strategy<Value = u64>
+{
+	prop::sample::select(ACCOUNTS.to_vec())
+}
+
+/* A few ids past the max owned kitties so invalid ids are hit too */
+fn kitty_id() -> impl Strategy<Value = u32>
+{
+	0u32..16
+}
+
+/* Some top ups more than the poorer accounts can pay */
+fn top_up() -> impl Strategy<Value = Option<TopUp<u128>>>
+{
+	prop_oneof![
+		Just(None),
+		(0u128..12_000).prop_map(|amount| Some(TopUp::FromProposer(amount))),
+		(0u128..12_000).prop_map(|amount| Some(TopUp::FromCounterparty(amount))),
+	]
+}
+
+fn action() -> impl Strategy<Value = Action>
+{
+	prop_oneof![
+		account().prop_map(Action::Create),
+		(account(), kitty_id(), kitty_id()).prop_map(|(who, kitty_id_1, kitty_id_2)| Action::Breed(who, kitty_id_1, kitty_id_2)),
+		(account(), kitty_id(), account()).prop_map(|(who, kitty_id, to)| Action::Transfer(who, kitty_id, to)),
+		(account(), kitty_id(), 500u128..12_000).prop_map(|(who, kitty_id, price)| Action::Sell(who, kitty_id, price)),
+		(account(), kitty_id()).prop_map(|(who, kitty_id)| Action::Buy(who, kitty_id)),
+		(account(), kitty_id()).prop_map(|(who, kitty_id)| Action::Burn(who, kitty_id)),
+		(account(), kitty_id(), 0u128..12_000, 0u64..32).prop_map(|(who, kitty_id, amount, expiry)| Action::MakeOffer(who, kitty_id, amount, expiry)),
+		(account(), kitty_id()).prop_map(|(who, kitty_id)| Action::CancelOffer(who, kitty_id)),
+		(account(), kitty_id(), account()).prop_map(|(who, kitty_id, buyer)| Action::AcceptOffer(who, kitty_id, buyer)),
+		(account(), kitty_id(), 0u128..12_000, 0u128..12_000, 0u64..4).prop_map(|(who, kitty_id, amount, repayment, duration)| Action::RequestLoan(who, kitty_id, amount, repayment, duration)),
+		(account(), kitty_id()).prop_map(|(who, kitty_id)| Action::FundLoan(who, kitty_id)),
+		(account(), kitty_id()).prop_map(|(who, kitty_id)| Action::RepayLoan(who, kitty_id)),
+		(account(), kitty_id()).prop_map(|(who, kitty_id)| Action::ClaimCollateral(who, kitty_id)),
+		(account(), kitty_id(), kitty_id(), top_up()).prop_map(|(who, my_kitty, their_kitty, top_up)| Action::ProposeSwap(who, my_kitty, their_kitty, top_up)),
+		(account(), kitty_id()).prop_map(|(who, my_kitty)| Action::AcceptSwap(who, my_kitty)),
+		Just(Action::NextBlock),
+	]
+}
+
+/* Top up held on the proposer until the swap ends */
+fn reserved_top_up(swap: &Swap<u64, u32, u128>) -> u128
+{
+	match swap.top_up
+	{
+		Some(TopUp::FromProposer(amount)) => amount,
+		_ => 0,
+	}
+}
+
+fn assert_invariants()
+{
+	/* Every owned kitty maps back to its owner, once */
+	let mut owned_total = 0;
+	for (owner, owned) in OwnedKitty::<Test>::iter()
+	{
+		for (i, kitty_id) in owned.iter().enumerate()
+		{
+			assert_eq!(KittyOwnedBy::<Test>::get(kitty_id), Some(owner), "kitty {} in owned vec of {}", kitty_id, owner);
+			assert!(!owned[..i].contains(kitty_id), "kitty {} owned twice by {}", kitty_id, owner);
+		}
+		owned_total += owned.len();
+	}
+
+	/* Every kitty owner lists the kitty */
+	let mut kitties_total = 0;
+	for (kitty_id, owner) in KittyOwnedBy::<Test>::iter()
+	{
+		assert!(OwnedKitty::<Test>::get(owner).unwrap_or_default().contains(&kitty_id), "kitty {} missing from owned vec of {}", kitty_id, owner);
+		assert!(Kitties::<Test>::contains_key(kitty_id), "kitty {} owned without dna", kitty_id);
+		kitties_total += 1;
+	}
+	assert_eq!(owned_total, kitties_total);
+
+	/* Offers are only open on existing kitties */
+	for (kitty_id, buyer, _) in Offers::<Test>::iter()
+	{
+		assert!(KittyOwnedBy::<Test>::contains_key(kitty_id), "offer of {} on burned kitty {}", buyer, kitty_id);
+	}
+
+	/* Only owned kitties are listed */
+	for (kitty_id, _) in SaleList::<Test>::iter()
+	{
+		assert!(KittyOwnedBy::<Test>::contains_key(kitty_id), "kitty {} listed without owner", kitty_id);
+	}
+
+	/* Loan collateral stays with the borrower, lenders hold room for it once funded */
+	let mut lent = [0u32; ACCOUNTS.len()];
+	for (kitty_id, loan) in Loans::<Test>::iter()
+	{
+		assert_eq!(KittyOwnedBy::<Test>::get(kitty_id), Some(loan.borrower), "loan {} collateral moved", kitty_id);
+		assert!(!SaleList::<Test>::contains_key(kitty_id), "loan {} collateral listed", kitty_id);
+		assert_eq!(loan.lender.is_some(), loan.deadline.is_some(), "loan {} funded without deadline", kitty_id);
+		if let Some(lender) = loan.lender
+		{
+			lent[ACCOUNTS.iter().position(|account| *account == lender).unwrap()] += 1;
+		}
+	}
+
+	/* Swaps are proposed by the owner of the offered kitty */
+	for (kitty_id, swap) in Swaps::<Test>::iter()
+	{
+		assert_eq!(KittyOwnedBy::<Test>::get(kitty_id), Some(swap.proposer), "swap of {} by non owner", kitty_id);
+	}
+
+	/* Reserve is held for each owned and lent kitty, open offer and proposer top up */
+	let mut reserved_total = 0;
+	for (i, account) in ACCOUNTS.into_iter().enumerate()
+	{
+		assert_eq!(LentCount::<Test>::get(account), lent[i], "lent count of {}", account);
+
+		let owned = OwnedKitty::<Test>::get(account).map(|owned| owned.len()).unwrap_or_default() as u128;
+		let offers: u128 = Offers::<Test>::iter()
+			.filter(|(_, buyer, _)| *buyer == account)
+			.map(|(_, _, offer)| offer.amount)
+			.sum();
+		let top_ups: u128 = Swaps::<Test>::iter_values()
+			.filter(|swap| swap.proposer == account)
+			.map(|swap| reserved_top_up(&swap))
+			.sum();
+
+		let reserved = Balances::reserved_balance(&account);
+		assert_eq!(reserved, (owned + lent[i] as u128) * Reserved::get() + offers + top_ups, "reserved of {}", account);
+		reserved_total += reserved;
+	}
+	let lent_total: u32 = lent.iter().sum();
+	let held_total: u128 = Offers::<Test>::iter_values().map(|offer| offer.amount).sum::<u128>() +
+		Swaps::<Test>::iter_values().map(|swap| reserved_top_up(&swap)).sum::<u128>();
+	assert_eq!(reserved_total, (kitties_total as u128 + lent_total as u128) * Reserved::get() + held_total);
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(64))]
+
+	#[test]
+	fn marketplace_invariants_hold(actions in prop::collection::vec(action(), 1..48))
+	{
+		new_test_ext().execute_with(|| {
+			for action in actions
+			{
+				match action.into_call()
+				{
+					Some((who, call)) => { let _ = call.dispatch(Origin::signed(who)); },
+					None => run_to_block(System::block_number() + 1),
+				}
+
+				assert_invariants();
+			}
+		});
+	}
+}
@@ -8,26 +8,142 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(test)]
+mod invariants;
+
+mod impl_nonfungible;
+
 #[frame_support::pallet]
 pub mod pallet {
-    use frame_support::{pallet_prelude::{*, DispatchResultWithPostInfo}};
+    use frame_support::{pallet_prelude::{*, DispatchResultWithPostInfo}, transactional};
     use frame_system::{pallet_prelude::{*, OriginFor}, ensure_signed};
-    use frame_support::traits::{Randomness, Currency, ReservableCurrency};
+    use frame_support::traits::{Randomness, Currency, ReservableCurrency, BalanceStatus, ExistenceRequirement, OnUnbalanced, WithdrawReasons};
     use sp_io::hashing::blake2_128;
-    use sp_std::{fmt::Debug};
-    use sp_runtime::traits::{Bounded, AtLeast32BitUnsigned};
+    use sp_std::{fmt::Debug, vec::Vec};
+    #[cfg(feature = "std")]
+    use serde::{Deserialize, Serialize};
+    use sp_runtime::{Permill, traits::{Bounded, AtLeast32BitUnsigned, Saturating, Zero}};
 
-    type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    type BalanceOf<T, I = ()> = <<T as Config<I>>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    type NegativeImbalanceOf<T, I = ()> = <<T as Config<I>>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
 
     #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen,)]
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
     pub struct Kitty(pub [u8; 16]);
 
+    /* Kitty details for runtime api queries */
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo,)]
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    pub struct KittyDetails<AccountId, Balance>
+    {
+        /* None until dna revealed */
+        pub dna: Option<Kitty>,
+        pub owner: AccountId,
+        pub creator: Option<AccountId>,
+        pub name: Option<Vec<u8>>,
+        /* None when not for sale */
+        pub price: Option<Balance>,
+    }
+
+    /* Page of runtime api query results, next is the start of the following page if any */
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo,)]
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    pub struct Page<Item, Cursor>
+    {
+        pub items: Vec<Item>,
+        pub next: Option<Cursor>,
+    }
+
+    /* Most ids checked by one page query */
+    pub const MAX_PAGE_LIMIT: u32 = 100;
+
+    /* Kitty info besides dna */
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen,)]
+    pub struct KittyInfo<AccountId>
+    {
+        /* Account created or bred the kitty, receives royalty on sales */
+        pub creator: AccountId,
+    }
+
+    /* Kitty committed but dna not revealed yet */
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen,)]
+    pub struct PendingKitty<KittyIndex>
+    {
+        /* Randomness known at commit, mixed with randomness known at reveal */
+        pub seed: [u8; 16],
+        /* Parents dna when bred */
+        pub parents: Option<(Kitty, Kitty)>,
+        /* Parents locked for breeding until reveal */
+        pub locked: (Option<KittyIndex>, Option<KittyIndex>),
+    }
+
+    /* Kitty name or attribute value, deposit reserved on depositor until replaced, cleared or burned */
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen,)]
+    pub struct Metadata<Value, AccountId, Balance>
+    {
+        pub value: Value,
+        pub depositor: AccountId,
+        pub deposit: Balance,
+    }
+
+    /* Currency loan with kitty as collateral, lender set when funded */
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen,)]
+    pub struct Loan<AccountId, Balance, BlockNumber>
+    {
+        pub borrower: AccountId,
+        pub lender: Option<AccountId>,
+        /* Paid by lender to borrower on funding */
+        pub amount: Balance,
+        /* Paid by borrower to lender on repay */
+        pub repayment: Balance,
+        /* Blocks from funding until lender can claim the kitty */
+        pub duration: BlockNumber,
+        /* Set when funded */
+        pub deadline: Option<BlockNumber>,
+    }
+
+    /* Currency paid on top of a swap, and by which side */
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen,)]
+    pub enum TopUp<Balance>
+    {
+        /* Reserved on proposer until accepted or cancelled */
+        FromProposer(Balance),
+        /* Paid by counterparty on accept */
+        FromCounterparty(Balance),
+    }
+
+    /* Proposed exchange of proposer kitty for another owners kitty */
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen,)]
+    pub struct Swap<AccountId, KittyIndex, Balance>
+    {
+        pub proposer: AccountId,
+        pub their_kitty: KittyIndex,
+        pub top_up: Option<TopUp<Balance>>,
+    }
+
+    /* Buyer offer on a kitty, amount is reserved on buyer until expiry */
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen,)]
+    pub struct Offer<Balance, BlockNumber>
+    {
+        pub amount: Balance,
+        pub expiry: BlockNumber,
+    }
+
     #[pallet::config]
-    pub trait Config: frame_system::Config
+    pub trait Config<I: 'static = ()>: frame_system::Config
     {
-        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+        type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
         
+        /* Source for kitty dna, e.g. collective flip, BABE VRF, or a deterministic source in tests */
         type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+
+        /* Blocks between commit on create/breed and dna reveal, zero reveals immediately */
+        #[pallet::constant]
+        type RevealDelay: Get<Self::BlockNumber>;
+
+        /* Allowed kitties revealed in the same block */
+        #[pallet::constant]
+        type MaxPendingReveals: Get<u32>;
         
         type KittyIndex: Parameter + AtLeast32BitUnsigned + Default + Copy + Bounded + MaxEncodedLen;
 
@@ -35,61 +151,233 @@ pub mod pallet {
 
         /* Reserved mount for each kitty */
         #[pallet::constant]
-        type Reserved: Get<BalanceOf<Self>>;
+        type Reserved: Get<BalanceOf<Self, I>>;
 
         /* Allowed owned kitties for each account */
         #[pallet::constant]
         type MaxOwnedAllowed: Get<u32>;
+
+        /* Lowest price a kitty can be listed for */
+        #[pallet::constant]
+        type MinimumPrice: Get<BalanceOf<Self, I>>;
+
+        /* Max bytes of kitty name, attribute key and attribute value */
+        #[pallet::constant]
+        type StringLimit: Get<u32>;
+
+        /* Allowed attributes for each kitty */
+        #[pallet::constant]
+        type MaxAttributes: Get<u32>;
+
+        /* Reserved amount for each byte of name, attribute key and value */
+        #[pallet::constant]
+        type DepositPerByte: Get<BalanceOf<Self, I>>;
+
+        /* Allowed offers expiring in the same block */
+        #[pallet::constant]
+        type MaxExpiringOffers: Get<u32>;
+
+        /* Blocks an offer can stay open for */
+        #[pallet::constant]
+        type MaxOfferDuration: Get<Self::BlockNumber>;
+
+        /* Marketplace fee taken from each sale price */
+        #[pallet::constant]
+        type MarketplaceFee: Get<Permill>;
+
+        /* Royalty paid to kitty creator from each sale price */
+        #[pallet::constant]
+        type CreatorRoyalty: Get<Permill>;
+
+        /* Handler for marketplace fee, e.g. deposit to treasury */
+        type OnMarketplaceFee: OnUnbalanced<NegativeImbalanceOf<Self, I>>;
     }
 
     #[pallet::type_value]
-    pub fn GetDefaultValue<T: Config>() -> T::KittyIndex
+    pub fn GetDefaultValue<T: Config<I>, I: 'static>() -> T::KittyIndex
     {
         <T::KittyIndex as Default>::default()
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
-    pub struct Pallet<T>(_);
+    pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
     /* Keep track all kitties */
     #[pallet::storage]
     #[pallet::getter(fn kitty_count)]
-    pub type KittyCount<T: Config> = StorageValue<_, T::KittyIndex, ValueQuery, GetDefaultValue<T>>;
+    pub type KittyCount<T: Config<I>, I: 'static = ()> = StorageValue<_, T::KittyIndex, ValueQuery, GetDefaultValue<T, I>>;
 
     /* Storage for all kitties with index */
     #[pallet::storage]
     #[pallet::getter(fn kitties)]
-    pub type Kitties<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, Kitty>;
+    pub type Kitties<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::KittyIndex, Kitty>;
+
+    /* Kitties waiting for dna reveal */
+    #[pallet::storage]
+    #[pallet::getter(fn pending_kitties)]
+    pub type PendingKitties<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::KittyIndex, PendingKitty<T::KittyIndex>>;
+
+    /* Pending breeds locking each parent, locked kitty can not be listed, transferred or burned, same for loan collateral */
+    #[pallet::storage]
+    #[pallet::getter(fn breeding_locks)]
+    pub type BreedingLocks<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::KittyIndex, u32, ValueQuery>;
+
+    /* Kitties revealed at each block */
+    #[pallet::storage]
+    #[pallet::getter(fn reveal_queue)]
+    pub type RevealQueue<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::BlockNumber, BoundedVec<T::KittyIndex, T::MaxPendingReveals>, ValueQuery>;
+
+    /* Storage for kitty info with index */
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_info)]
+    pub type KittyInfos<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::KittyIndex, KittyInfo<T::AccountId>>;
 
     /* Storage for all kitties under each account  */
     #[pallet::storage]
     #[pallet::getter(fn owned_kitty)]
-    pub type OwnedKitty<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<T::KittyIndex, T::MaxOwnedAllowed>>;
+    pub type OwnedKitty<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<T::KittyIndex, T::MaxOwnedAllowed>>;
     
     /* Storage for kitty id with owner account */
     #[pallet::storage]
     #[pallet::getter(fn kitty_owned_by)]
-    pub type KittyOwnedBy<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
+    pub type KittyOwnedBy<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
 
     /* Kitties sale list, none means not for sale */
     #[pallet::storage]
 	#[pallet::getter(fn sale_list)]
-	pub type SaleList<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, Option<BalanceOf<T>>, ValueQuery>;
+	pub type SaleList<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::KittyIndex, BalanceOf<T, I>>;
+
+    /* Approved operator for each kitty, cleared on transfer */
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_approval)]
+    pub type KittyApprovals<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
+
+    /* Operators approved for all kitties of an owner */
+    #[pallet::storage]
+    #[pallet::getter(fn operator_approval)]
+    pub type OperatorApprovals<T: Config<I>, I: 'static = ()> = StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, bool, ValueQuery>;
+
+    /* Offers on each kitty from each buyer */
+    #[pallet::storage]
+    #[pallet::getter(fn offers)]
+    pub type Offers<T: Config<I>, I: 'static = ()> = StorageDoubleMap<_, Blake2_128Concat, T::KittyIndex, Blake2_128Concat, T::AccountId, Offer<BalanceOf<T, I>, T::BlockNumber>>;
+
+    /* Offers expiring at each block */
+    #[pallet::storage]
+    #[pallet::getter(fn offer_expiries)]
+    pub type OfferExpiries<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::BlockNumber, BoundedVec<(T::KittyIndex, T::AccountId), T::MaxExpiringOffers>, ValueQuery>;
+
+    /* Loans with kitty as collateral, kitty locked until repaid, cancelled or claimed */
+    #[pallet::storage]
+    #[pallet::getter(fn loans)]
+    pub type Loans<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::KittyIndex, Loan<T::AccountId, BalanceOf<T, I>, T::BlockNumber>>;
+
+    /* Funded loans of each lender, counted as owned kitties so the collateral always fits on claim */
+    #[pallet::storage]
+    #[pallet::getter(fn lent_count)]
+    pub type LentCount<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /* Swaps proposed for each proposer kitty, cleared when it changes owner */
+    #[pallet::storage]
+    #[pallet::getter(fn swaps)]
+    pub type Swaps<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::KittyIndex, Swap<T::AccountId, T::KittyIndex, BalanceOf<T, I>>>;
+
+    /* Kitty names set by owner */
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_name)]
+    pub type KittyNames<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::KittyIndex, Metadata<BoundedVec<u8, T::StringLimit>, T::AccountId, BalanceOf<T, I>>>;
+
+    /* Kitty attributes set by owner, by kitty id and key */
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_attribute)]
+    pub type KittyAttributes<T: Config<I>, I: 'static = ()> = StorageDoubleMap<_, Blake2_128Concat, T::KittyIndex, Blake2_128Concat, BoundedVec<u8, T::StringLimit>, Metadata<BoundedVec<u8, T::StringLimit>, T::AccountId, BalanceOf<T, I>>>;
+
+    /* Number of attributes on each kitty */
+    #[pallet::storage]
+    #[pallet::getter(fn attribute_count)]
+    pub type AttributeCount<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::KittyIndex, u32, ValueQuery>;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig<T: Config<I>, I: 'static = ()>
+    {
+        /* Founder kitties as (owner, dna), reserved from endowed balances */
+        pub kitties: Vec<(T::AccountId, [u8; 16])>,
+        pub phantom: PhantomData<I>,
+    }
+
+    #[cfg(feature = "std")]
+    impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I>
+    {
+        fn default() -> Self
+        {
+            Self { kitties: Vec::new(), phantom: PhantomData }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I>
+    {
+        fn build(&self)
+        {
+            for (owner, dna) in self.kitties.iter()
+            {
+                Pallet::<T, I>::mint(owner, *dna).expect("Founder kitty owner must be endowed and under MaxOwnedAllowed");
+            }
+        }
+    }
 
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
-    pub enum Event<T: Config>
+    pub enum Event<T: Config<I>, I: 'static = ()>
     {
         KittyCreated(T::AccountId, T::KittyIndex, Kitty),
         KittyBreed(T::AccountId, T::KittyIndex, Kitty),
+        /* [owner, kitty_id, reveal_at] */
+        KittyCommitted(T::AccountId, T::KittyIndex, T::BlockNumber),
         KittyTransferred(T::AccountId, T::AccountId, T::KittyIndex),
-        KittyOnSale(T::AccountId, T::KittyIndex, Option<BalanceOf<T>>),
-        KittySaled(T::AccountId, T::AccountId, T::KittyIndex, Option<BalanceOf<T>>),
+        KittyOnSale(T::AccountId, T::KittyIndex, BalanceOf<T, I>),
+        KittyUnlisted(T::AccountId, T::KittyIndex),
+        /* [seller, buyer, kitty_id, price, marketplace_fee, creator_royalty] */
+        KittySaled(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T, I>, BalanceOf<T, I>, BalanceOf<T, I>),
+        KittyBurned(T::AccountId, T::KittyIndex),
+        /* [owner, operator, kitty_id] */
+        KittyApproved(T::AccountId, T::AccountId, T::KittyIndex),
+        /* [owner, kitty_id] */
+        KittyApprovalCancelled(T::AccountId, T::KittyIndex),
+        /* [owner, operator, approved] */
+        ApprovalForAll(T::AccountId, T::AccountId, bool),
+        OfferMade(T::AccountId, T::KittyIndex, BalanceOf<T, I>, T::BlockNumber),
+        OfferCancelled(T::AccountId, T::KittyIndex, BalanceOf<T, I>),
+        /* [seller, buyer, kitty_id, amount, marketplace_fee, creator_royalty] */
+        OfferAccepted(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T, I>, BalanceOf<T, I>, BalanceOf<T, I>),
+        OfferExpired(T::AccountId, T::KittyIndex, BalanceOf<T, I>),
+        /* [owner, kitty_id, name] */
+        KittyNamed(T::AccountId, T::KittyIndex, BoundedVec<u8, T::StringLimit>),
+        KittyNameCleared(T::AccountId, T::KittyIndex),
+        /* [owner, kitty_id, key, value] */
+        AttributeSet(T::AccountId, T::KittyIndex, BoundedVec<u8, T::StringLimit>, BoundedVec<u8, T::StringLimit>),
+        /* [owner, kitty_id, key] */
+        AttributeCleared(T::AccountId, T::KittyIndex, BoundedVec<u8, T::StringLimit>),
+        /* [borrower, kitty_id, amount, repayment, duration] */
+        LoanRequested(T::AccountId, T::KittyIndex, BalanceOf<T, I>, BalanceOf<T, I>, T::BlockNumber),
+        LoanCancelled(T::AccountId, T::KittyIndex),
+        /* [lender, borrower, kitty_id, deadline] */
+        LoanFunded(T::AccountId, T::AccountId, T::KittyIndex, T::BlockNumber),
+        /* [borrower, lender, kitty_id, repayment] */
+        LoanRepaid(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T, I>),
+        /* [lender, borrower, kitty_id] */
+        CollateralClaimed(T::AccountId, T::AccountId, T::KittyIndex),
+        /* [proposer, my_kitty, their_kitty, top_up] */
+        SwapProposed(T::AccountId, T::KittyIndex, T::KittyIndex, Option<TopUp<BalanceOf<T, I>>>),
+        /* [proposer, my_kitty] */
+        SwapCancelled(T::AccountId, T::KittyIndex),
+        /* [proposer, counterparty, my_kitty, their_kitty, top_up] */
+        SwapAccepted(T::AccountId, T::AccountId, T::KittyIndex, T::KittyIndex, Option<TopUp<BalanceOf<T, I>>>),
     }
 
     #[pallet::error]
-    pub enum Error<T>
+    pub enum Error<T, I = ()>
     {
         InvalidKittyId,
         SameKittyId,
@@ -99,13 +387,64 @@ pub mod pallet {
         NotForSale,
         NotEnoughBalanceBuy,
         ExceedMaxOwned,
+        InvalidExpiry,
+        OfferAlreadyExists,
+        OfferNotFound,
+        TooManyExpiringOffers,
+        TooManyPendingReveals,
+        NotApproved,
+        KittyListed,
+        KittyLocked,
+        PriceTooLow,
+        BadMetadata,
+        TooManyAttributes,
+        MetadataNotFound,
+        LoanNotFound,
+        LoanAlreadyFunded,
+        LoanNotFunded,
+        LoanExpired,
+        LoanNotExpired,
+        SwapAlreadyExists,
+        SwapNotFound,
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I>
+    {
+        fn on_initialize(now: T::BlockNumber) -> Weight
+        {
+            /* Take offers expiring at this block */
+            let expiring = OfferExpiries::<T, I>::take(now);
+            let count = expiring.len() as Weight;
+
+            /* Unreserve on buyers */
+            for (kitty_id, buyer) in expiring.into_iter()
+            {
+                if let Some(offer) = Offers::<T, I>::take(kitty_id, &buyer)
+                {
+                    T::Currency::unreserve(&buyer, offer.amount);
+                    Self::deposit_event(Event::<T, I>::OfferExpired(buyer, kitty_id, offer.amount));
+                }
+            }
+
+            /* Reveal kitties committed for this block */
+            let revealing = RevealQueue::<T, I>::take(now);
+            let reveals = revealing.len() as Weight;
+
+            for kitty_id in revealing.into_iter()
+            {
+                if let Some(pending) = PendingKitties::<T, I>::take(kitty_id)
+                {
+                    Self::reveal(kitty_id, pending);
+                }
+            }
+
+            T::DbWeight::get().reads_writes(2 + count + reveals * 2, 2 + count * 2 + reveals * 2)
+        }
+    }
 
     #[pallet::call]
-    impl<T: Config> Pallet<T>
+    impl<T: Config<I>, I: 'static> Pallet<T, I>
     {
         #[pallet::weight(10_000)]
         pub fn create(origin: OriginFor<T>) -> DispatchResultWithPostInfo
@@ -113,13 +452,11 @@ pub mod pallet {
             /* Check signature */
             let sender = ensure_signed(origin)?;
 
-            /* Generate random value from sender */
-            let dna = Self::random_value(&sender);
+            /* Generate random seed from sender */
+            let seed = Self::random_value(&sender);
 
-            /* Mint kitty with reserve */
-            let minted = Self::mint(&sender, dna)?;
-
-            Self::deposit_event(Event::<T>::KittyCreated(minted.0, minted.1, minted.2));
+            /* Commit kitty with reserve, dna revealed later */
+            Self::commit(&sender, seed, None)?;
 
             Ok(().into())
         }
@@ -131,22 +468,19 @@ pub mod pallet {
             let sender = ensure_signed(origin)?;
 
             /* Make sure not same kitty & invalid kitty Id */
-            ensure!(kitty_id_1 != kitty_id_2, Error::<T>::SameKittyId);
-            let kitty_1 = Self::get_kitty(kitty_id_1).map_err(|_| Error::<T>::InvalidKittyId)?;
-            let kitty_2 = Self::get_kitty(kitty_id_2).map_err(|_| Error::<T>::InvalidKittyId)?;
-
-            /* Generate random seed  */
-            let selector = Self::random_value(&sender);
+            ensure!(kitty_id_1 != kitty_id_2, Error::<T, I>::SameKittyId);
+            let kitty_1 = Self::get_kitty(kitty_id_1).map_err(|_| Error::<T, I>::InvalidKittyId)?;
+            let kitty_2 = Self::get_kitty(kitty_id_2).map_err(|_| Error::<T, I>::InvalidKittyId)?;
 
-            /* Use random seed generating bread dna */
-            let mut dna = [0u8; 16];
-            for i in 0..kitty_1.0.len(){
-                dna[i] = (kitty_1.0[i] & selector[i]) | (kitty_2.0[i] & !selector[i]);
-            }
+            /* Only owner of both parents can breed, so both can be locked until reveal */
+            Self::ensure_owner(&sender, kitty_id_1)?;
+            Self::ensure_owner(&sender, kitty_id_2)?;
 
-            let minted = Self::mint(&sender, dna)?;
+            /* Generate random seed  */
+            let seed = Self::random_value(&sender);
 
-            Self::deposit_event(Event::<T>::KittyBreed(minted.0, minted.1, minted.2));
+            /* Commit kitty with reserve, bred dna revealed later */
+            Self::commit(&sender, seed, Some(((kitty_id_1, kitty_1), (kitty_id_2, kitty_2))))?;
 
             Ok(().into())
         }
@@ -158,170 +492,1065 @@ pub mod pallet {
             let sender = ensure_signed(origin)?;
 
             /* Check kitty exist */
-            Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
+            Self::get_kitty(kitty_id).map_err(|_| Error::<T, I>::InvalidKittyId)?;
 
-            /* Only owner can transfer */
-            ensure!(Self::kitty_owned_by(kitty_id) == Some(sender.clone()), Error::<T>::NotOwner);
-            
-            /* Reserve on target account */
-            T::Currency::reserve(&who, T::Reserved::get()).map_err(|_| Error::<T>::NotEnoughBalanceReserved)?;
-            /* Unreserve on source account */
-            T::Currency::unreserve(&sender, T::Reserved::get());
+            /* Only owner or approved operator can transfer */
+            let owner = Self::ensure_owner_or_approved(&sender, kitty_id)?;
 
-            /* Transfer kitty */
-            KittyOwnedBy::<T>::insert(kitty_id, who.clone());
+            /* Locked kitty can not be transferred */
+            Self::ensure_unlocked(kitty_id)?;
 
-            /* Update ownership map */
-            Self::update_to_new_owner(kitty_id, sender.clone(), who.clone())?;
+            /* Transfer kitty with reserve, clear listing */
+            Self::do_transfer(kitty_id, &owner, &who)?;
 
             /* Post event */
-            Self::deposit_event(Event::KittyTransferred(sender, who, kitty_id));
+            Self::deposit_event(Event::KittyTransferred(owner, who, kitty_id));
 
             Ok(().into())
         }
 
         #[pallet::weight(10_000)]
-        pub fn sell(origin: OriginFor<T>, kitty_id: T::KittyIndex, price: Option<BalanceOf<T>>) -> DispatchResultWithPostInfo
+        pub fn sell(origin: OriginFor<T>, kitty_id: T::KittyIndex, price: BalanceOf<T, I>) -> DispatchResultWithPostInfo
         {
             /* Check signature */
             let sender = ensure_signed(origin)?;
 
-            /* Only owner can sell */
-            ensure!(Self::kitty_owned_by(kitty_id) == Some(sender.clone()), Error::<T>::NotOwner);
+            /* Check kitty exist */
+            Self::get_kitty(kitty_id).map_err(|_| Error::<T, I>::InvalidKittyId)?;
+
+            /* Only owner or approved operator can sell */
+            let owner = Self::ensure_owner_or_approved(&sender, kitty_id)?;
+
+            /* Locked kitty can not be listed */
+            Self::ensure_unlocked(kitty_id)?;
 
-            /* Add to sale list */
-            SaleList::<T>::insert(kitty_id, price);
+            /* Check price */
+            ensure!(price >= T::MinimumPrice::get(), Error::<T, I>::PriceTooLow);
+
+            /* Add to sale list, replace previous price */
+            SaleList::<T, I>::insert(kitty_id, price);
 
             /* Post event */
-            Self::deposit_event(Event::<T>::KittyOnSale(sender, kitty_id, price));
+            Self::deposit_event(Event::<T, I>::KittyOnSale(owner, kitty_id, price));
 
             Ok(().into())
         }
 
         #[pallet::weight(10_000)]
+        #[transactional]
         pub fn buy(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResultWithPostInfo
         {
             /* Check signature  */
             let buyer = ensure_signed(origin)?;
+
+            /* Get sale price */
+            let price = SaleList::<T, I>::get(kitty_id).ok_or(Error::<T, I>::NotForSale)?;
+
             /* Get owner account id */
-            let owner = KittyOwnedBy::<T>::get(kitty_id).unwrap();
+            let owner = KittyOwnedBy::<T, I>::get(kitty_id).ok_or(Error::<T, I>::InvalidKittyId)?;
 
             /* Can not buy from self */
-            ensure!(buyer.clone() != owner.clone(), Error::<T>::AlreadyOwned);
-
-            /* Get sale price */
-            let price = SaleList::<T>::get(kitty_id).ok_or(Error::<T>::NotForSale)?;
+            ensure!(buyer.clone() != owner.clone(), Error::<T, I>::AlreadyOwned);
 
             /* Check free balance is enough */
-            ensure!(T::Currency::free_balance(&buyer) > (price + T::Reserved::get()), Error::<T>::NotEnoughBalanceBuy);
+            ensure!(T::Currency::free_balance(&buyer) > price.saturating_add(T::Reserved::get()), Error::<T, I>::NotEnoughBalanceBuy);
 
-            /* Reserve on buyer */
-            T::Currency::reserve(&buyer, T::Reserved::get()).map_err(|_| Error::<T>::NotEnoughBalanceReserved)?;
-            /* Unreserve on owner */
-            T::Currency::unreserve(&owner, T::Reserved::get());
+            /* Transfer kitty with reserve, clear listing, reverted with payment on failure */
+            Self::do_transfer(kitty_id, &owner, &buyer)?;
 
-            /* Transfer cost price to owner */
-            T::Currency::transfer(&buyer, &owner, price, frame_support::traits::ExistenceRequirement::KeepAlive)?;
+            /* Pay price to owner, creator and marketplace */
+            let (fee, royalty) = Self::pay_for_kitty(kitty_id, &buyer, &owner, price, false)?;
 
-            /* Update owned by map */
-            KittyOwnedBy::<T>::insert(kitty_id, buyer.clone());
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::KittySaled(owner, buyer, kitty_id, price, fee, royalty));
 
-            /* Update ownership map */
-            Self::update_to_new_owner(kitty_id, owner.clone(), buyer.clone())?;
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn unlist(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
+
+            /* Only owner or approved operator can unlist */
+            let owner = Self::ensure_owner_or_approved(&sender, kitty_id)?;
 
             /* Remove from sale list */
-            SaleList::<T>::remove(kitty_id);
+            SaleList::<T, I>::take(kitty_id).ok_or(Error::<T, I>::NotForSale)?;
 
             /* Post event */
-            Self::deposit_event(Event::<T>::KittySaled(owner, buyer, kitty_id, Some(price)));
+            Self::deposit_event(Event::<T, I>::KittyUnlisted(owner, kitty_id));
 
             Ok(().into())
         }
 
-    }
+        #[pallet::weight(10_000)]
+        pub fn set_name(origin: OriginFor<T>, kitty_id: T::KittyIndex, name: Vec<u8>) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
 
-    impl<T: Config> Pallet<T>
-    {
-        fn random_value(sender: &T::AccountId) -> [u8; 16]
+            /* Only owner can name */
+            Self::ensure_owner(&sender, kitty_id)?;
+
+            /* Check name length */
+            let name: BoundedVec<u8, T::StringLimit> = name.try_into().map_err(|_| Error::<T, I>::BadMetadata)?;
+
+            /* Reserve for new name, refund old one */
+            let old = Self::kitty_name(kitty_id).map(|old| (old.depositor, old.deposit));
+            let deposit = Self::deposit_metadata(&sender, name.len(), old)?;
+
+            KittyNames::<T, I>::insert(kitty_id, Metadata { value: name.clone(), depositor: sender.clone(), deposit });
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::KittyNamed(sender, kitty_id, name));
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn clear_name(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResultWithPostInfo
         {
-            let payload = (
-                T::Randomness::random_seed(),
-                sender.clone(),
-                <frame_system::Pallet::<T>>::extrinsic_index(),
-            );
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
 
-            payload.using_encoded(blake2_128)
+            /* Only owner can clear name */
+            Self::ensure_owner(&sender, kitty_id)?;
+
+            /* Remove name and refund */
+            let old = KittyNames::<T, I>::take(kitty_id).ok_or(Error::<T, I>::MetadataNotFound)?;
+            T::Currency::unreserve(&old.depositor, old.deposit);
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::KittyNameCleared(sender, kitty_id));
+
+            Ok(().into())
         }
 
-        fn get_next_id() -> Result<T::KittyIndex, ()>
+        #[pallet::weight(10_000)]
+        pub fn set_attribute(origin: OriginFor<T>, kitty_id: T::KittyIndex, key: Vec<u8>, value: Vec<u8>) -> DispatchResultWithPostInfo
         {
-            let _max_index = T::KittyIndex::max_value();
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
 
-            match Self::kitty_count()
+            /* Only owner can set attributes */
+            Self::ensure_owner(&sender, kitty_id)?;
+
+            /* Check key and value length */
+            let key: BoundedVec<u8, T::StringLimit> = key.try_into().map_err(|_| Error::<T, I>::BadMetadata)?;
+            let value: BoundedVec<u8, T::StringLimit> = value.try_into().map_err(|_| Error::<T, I>::BadMetadata)?;
+
+            /* New key needs room */
+            let old = Self::kitty_attribute(kitty_id, &key).map(|old| (old.depositor, old.deposit));
+            let is_new = old.is_none();
+            ensure!(!is_new || Self::attribute_count(kitty_id) < T::MaxAttributes::get(), Error::<T, I>::TooManyAttributes);
+
+            /* Reserve for key and value, refund old one */
+            let deposit = Self::deposit_metadata(&sender, key.len() + value.len(), old)?;
+
+            if is_new
             {
-                a if a ==_max_index => Err(()),
-                val => Ok(val),
+                AttributeCount::<T, I>::mutate(kitty_id, |count| *count += 1);
             }
+
+            KittyAttributes::<T, I>::insert(kitty_id, &key, Metadata { value: value.clone(), depositor: sender.clone(), deposit });
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::AttributeSet(sender, kitty_id, key, value));
+
+            Ok(().into())
         }
 
-        fn get_kitty(kitty_id: T::KittyIndex) -> Result<Kitty, ()>
+        #[pallet::weight(10_000)]
+        pub fn clear_attribute(origin: OriginFor<T>, kitty_id: T::KittyIndex, key: Vec<u8>) -> DispatchResultWithPostInfo
         {
-            match Self::kitties(kitty_id)
-            {
-                Some(kitty) => Ok(kitty),
-                None => Err(()),
-            }
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
+
+            /* Only owner can clear attributes */
+            Self::ensure_owner(&sender, kitty_id)?;
+
+            /* Remove attribute and refund */
+            let key: BoundedVec<u8, T::StringLimit> = key.try_into().map_err(|_| Error::<T, I>::BadMetadata)?;
+            let old = KittyAttributes::<T, I>::take(kitty_id, &key).ok_or(Error::<T, I>::MetadataNotFound)?;
+            T::Currency::unreserve(&old.depositor, old.deposit);
+            AttributeCount::<T, I>::mutate(kitty_id, |count| *count = count.saturating_sub(1));
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::AttributeCleared(sender, kitty_id, key));
+
+            Ok(().into())
         }
 
-        fn update_to_new_owner(kitty_id: T::KittyIndex, from: T::AccountId, to: T::AccountId) -> Result<(), Error<T>>
+        #[pallet::weight(10_000)]
+        pub fn burn(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResultWithPostInfo
         {
-            /* Get old owners kitty vec */
-            let mut from_owned = OwnedKitty::<T>::get(&from).unwrap();
-            /* Get new owners kitty vec */
-            let mut to_owned = OwnedKitty::<T>::get(&to).unwrap_or(BoundedVec::<T::KittyIndex, T::MaxOwnedAllowed>::default());
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
 
-			/* Remove kitty from owners kitty vec, add to new owners kitty vec */
-			if let Some(ind) = from_owned.iter().position(|ids| *ids == kitty_id) 
-            {
-				let swap_id = from_owned.swap_remove(ind);
-                to_owned.try_push(swap_id.clone()).map_err(|_| Error::<T>::ExceedMaxOwned)?;
-                OwnedKitty::<T>::insert(to, to_owned);
+            /* Check kitty exist */
+            Self::get_kitty(kitty_id).map_err(|_| Error::<T, I>::InvalidKittyId)?;
 
-                return Ok(());
-			}
+            /* Only owner can burn */
+            ensure!(Self::kitty_owned_by(kitty_id) == Some(sender.clone()), Error::<T, I>::NotOwner);
+
+            /* Locked kitty can not be burned */
+            Self::ensure_unlocked(kitty_id)?;
 
-            Err(Error::<T>::InvalidKittyId)
+            /* Remove kitty and unreserve */
+            Self::do_burn(kitty_id, &sender)?;
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::KittyBurned(sender, kitty_id));
+
+            Ok(().into())
         }
 
-        fn mint(owner: &T::AccountId, dna: [u8; 16]) -> Result<(T::AccountId, T::KittyIndex, Kitty), Error<T>>
+        #[pallet::weight(10_000)]
+        pub fn approve(origin: OriginFor<T>, kitty_id: T::KittyIndex, operator: T::AccountId) -> DispatchResultWithPostInfo
         {
-            /* Get kitty id */
-            let kitty_id = Self::get_next_id().map_err(|_| Error::<T>::InvalidKittyId)?;
+            /* Check signature */
+            let owner = ensure_signed(origin)?;
 
-            /* Get reversed mount */
-            let reserved = T::Reserved::get();
+            /* Only owner can approve */
+            ensure!(Self::kitty_owned_by(kitty_id) == Some(owner.clone()), Error::<T, I>::NotOwner);
 
-            /* Reserve on owner */
-            T::Currency::reserve(&owner, reserved).map_err(|_| Error::<T>::NotEnoughBalanceReserved)?;
+            /* Save approval, replace previous one */
+            KittyApprovals::<T, I>::insert(kitty_id, operator.clone());
 
-            /* New kitty from dna */
-            let new_kitty = Kitty(dna);
-            
-            /* Save kitty to storage */
-            Kitties::<T>::insert(kitty_id, new_kitty.clone());
-            
-            /* Save kitty with owner */
-            KittyOwnedBy::<T>::insert(kitty_id, owner.clone());
-            
-            /* Update ownership map */
-            let mut owned_vec = OwnedKitty::<T>::get(&owner).unwrap_or(BoundedVec::<T::KittyIndex, T::MaxOwnedAllowed>::default());
-            owned_vec.try_push(kitty_id.clone()).map_err(|_| Error::<T>::ExceedMaxOwned)?;
-            OwnedKitty::<T>::insert(owner, owned_vec.clone());
-            
-            /* Update new kitty Id */
-            KittyCount::<T>::set(kitty_id + 1u32.into());
-            
-            Ok((owner.clone(), kitty_id, new_kitty))
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::KittyApproved(owner, operator, kitty_id));
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn cancel_approval(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let owner = ensure_signed(origin)?;
+
+            /* Only owner can cancel approval */
+            ensure!(Self::kitty_owned_by(kitty_id) == Some(owner.clone()), Error::<T, I>::NotOwner);
+
+            /* Remove approval */
+            KittyApprovals::<T, I>::take(kitty_id).ok_or(Error::<T, I>::NotApproved)?;
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::KittyApprovalCancelled(owner, kitty_id));
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn set_approval_for_all(origin: OriginFor<T>, operator: T::AccountId, approved: bool) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let owner = ensure_signed(origin)?;
+
+            /* Can not approve self */
+            ensure!(owner != operator, Error::<T, I>::AlreadyOwned);
+
+            /* Save or remove operator */
+            if approved
+            {
+                OperatorApprovals::<T, I>::insert(&owner, &operator, true);
+            }
+            else
+            {
+                OperatorApprovals::<T, I>::remove(&owner, &operator);
+            }
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::ApprovalForAll(owner, operator, approved));
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn make_offer(origin: OriginFor<T>, kitty_id: T::KittyIndex, amount: BalanceOf<T, I>, expiry: T::BlockNumber) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let buyer = ensure_signed(origin)?;
+
+            /* Check kitty exist */
+            Self::get_kitty(kitty_id).map_err(|_| Error::<T, I>::InvalidKittyId)?;
+
+            /* Can not offer on own kitty */
+            ensure!(Self::kitty_owned_by(kitty_id) != Some(buyer.clone()), Error::<T, I>::AlreadyOwned);
+
+            /* One offer for each buyer on a kitty */
+            ensure!(!Offers::<T, I>::contains_key(kitty_id, &buyer), Error::<T, I>::OfferAlreadyExists);
+
+            /* Check amount, same floor as listing price */
+            ensure!(amount >= T::MinimumPrice::get(), Error::<T, I>::PriceTooLow);
+
+            /* Expiry must be in the future, within max duration, and have room in expiry queue */
+            let now = <frame_system::Pallet::<T>>::block_number();
+            ensure!(expiry > now && expiry - now <= T::MaxOfferDuration::get(), Error::<T, I>::InvalidExpiry);
+            ensure!((OfferExpiries::<T, I>::decode_len(expiry).unwrap_or(0) as u32) < T::MaxExpiringOffers::get(), Error::<T, I>::TooManyExpiringOffers);
+
+            /* Reserve offer amount on buyer */
+            T::Currency::reserve(&buyer, amount).map_err(|_| Error::<T, I>::NotEnoughBalanceReserved)?;
+
+            /* Save offer and expiry */
+            OfferExpiries::<T, I>::try_append(expiry, (kitty_id, buyer.clone())).map_err(|_| Error::<T, I>::TooManyExpiringOffers)?;
+            Offers::<T, I>::insert(kitty_id, &buyer, Offer { amount, expiry });
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::OfferMade(buyer, kitty_id, amount, expiry));
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn cancel_offer(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let buyer = ensure_signed(origin)?;
+
+            /* Remove offer */
+            let offer = Self::remove_offer(kitty_id, &buyer)?;
+
+            /* Unreserve on buyer */
+            T::Currency::unreserve(&buyer, offer.amount);
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::OfferCancelled(buyer, kitty_id, offer.amount));
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        #[transactional]
+        pub fn accept_offer(origin: OriginFor<T>, kitty_id: T::KittyIndex, buyer: T::AccountId) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let owner = ensure_signed(origin)?;
+
+            /* Only owner can accept */
+            ensure!(Self::kitty_owned_by(kitty_id) == Some(owner.clone()), Error::<T, I>::NotOwner);
+
+            /* Locked kitty can not be sold */
+            Self::ensure_unlocked(kitty_id)?;
+
+            /* Get offer */
+            let offer = Self::offers(kitty_id, &buyer).ok_or(Error::<T, I>::OfferNotFound)?;
+
+            /* Transfer kitty with reserve, clear listing */
+            Self::do_transfer(kitty_id, &owner, &buyer)?;
+
+            /* Pay reserved offer amount to owner, creator and marketplace */
+            let (fee, royalty) = Self::pay_for_kitty(kitty_id, &buyer, &owner, offer.amount, true)?;
+
+            /* Remove offer */
+            Self::remove_offer(kitty_id, &buyer)?;
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::OfferAccepted(owner, buyer, kitty_id, offer.amount, fee, royalty));
+
+            Ok(().into())
+        }
+        #[pallet::weight(10_000)]
+        pub fn request_loan(origin: OriginFor<T>, kitty_id: T::KittyIndex, amount: BalanceOf<T, I>, repayment: BalanceOf<T, I>, duration: T::BlockNumber) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
+
+            /* Check kitty exist */
+            Self::get_kitty(kitty_id).map_err(|_| Error::<T, I>::InvalidKittyId)?;
+
+            /* Only owner can pledge */
+            Self::ensure_owner(&sender, kitty_id)?;
+
+            /* Locked kitty can not be pledged */
+            Self::ensure_unlocked(kitty_id)?;
+
+            /* Lock kitty, listing cleared */
+            if SaleList::<T, I>::take(kitty_id).is_some()
+            {
+                Self::deposit_event(Event::<T, I>::KittyUnlisted(sender.clone(), kitty_id));
+            }
+            Loans::<T, I>::insert(kitty_id, Loan { borrower: sender.clone(), lender: None, amount, repayment, duration, deadline: None });
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::LoanRequested(sender, kitty_id, amount, repayment, duration));
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn cancel_loan_request(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
+
+            /* Only borrower can cancel, before funding */
+            let loan = Self::loans(kitty_id).ok_or(Error::<T, I>::LoanNotFound)?;
+            ensure!(loan.borrower == sender, Error::<T, I>::NotOwner);
+            ensure!(loan.lender.is_none(), Error::<T, I>::LoanAlreadyFunded);
+
+            /* Unlock kitty */
+            Loans::<T, I>::remove(kitty_id);
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::LoanCancelled(sender, kitty_id));
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        #[transactional]
+        pub fn fund_loan(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let lender = ensure_signed(origin)?;
+
+            /* Get unfunded loan */
+            let mut loan = Self::loans(kitty_id).ok_or(Error::<T, I>::LoanNotFound)?;
+            ensure!(loan.lender.is_none(), Error::<T, I>::LoanAlreadyFunded);
+
+            /* Can not lend to self */
+            ensure!(lender != loan.borrower, Error::<T, I>::AlreadyOwned);
+
+            /* Lender keeps room and reserve for the collateral until the loan ends */
+            Self::ensure_can_own(&lender)?;
+            T::Currency::reserve(&lender, T::Reserved::get()).map_err(|_| Error::<T, I>::NotEnoughBalanceReserved)?;
+            LentCount::<T, I>::mutate(&lender, |count| *count = count.saturating_add(1));
+
+            /* Pay amount to borrower */
+            T::Currency::transfer(&lender, &loan.borrower, loan.amount, ExistenceRequirement::KeepAlive)?;
+
+            /* Deadline counts from funding */
+            let deadline = <frame_system::Pallet::<T>>::block_number().saturating_add(loan.duration);
+            loan.lender = Some(lender.clone());
+            loan.deadline = Some(deadline);
+            Loans::<T, I>::insert(kitty_id, loan.clone());
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::LoanFunded(lender, loan.borrower, kitty_id, deadline));
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        #[transactional]
+        pub fn repay_loan(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
+
+            /* Get funded loan */
+            let loan = Self::loans(kitty_id).ok_or(Error::<T, I>::LoanNotFound)?;
+            ensure!(loan.borrower == sender, Error::<T, I>::NotOwner);
+            let (lender, deadline) = loan.lender.zip(loan.deadline).ok_or(Error::<T, I>::LoanNotFunded)?;
+
+            /* Repay until deadline */
+            ensure!(<frame_system::Pallet::<T>>::block_number() <= deadline, Error::<T, I>::LoanExpired);
+
+            /* Pay repayment to lender and unlock kitty */
+            T::Currency::transfer(&sender, &lender, loan.repayment, ExistenceRequirement::KeepAlive)?;
+            Loans::<T, I>::remove(kitty_id);
+
+            /* Release room and reserve held for the collateral */
+            T::Currency::unreserve(&lender, T::Reserved::get());
+            LentCount::<T, I>::mutate(&lender, |count| *count = count.saturating_sub(1));
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::LoanRepaid(sender, lender, kitty_id, loan.repayment));
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        #[transactional]
+        pub fn claim_collateral(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
+
+            /* Only lender can claim, after deadline */
+            let loan = Self::loans(kitty_id).ok_or(Error::<T, I>::LoanNotFound)?;
+            ensure!(loan.lender.as_ref() == Some(&sender), Error::<T, I>::NotOwner);
+            let deadline = loan.deadline.ok_or(Error::<T, I>::LoanNotFunded)?;
+            ensure!(<frame_system::Pallet::<T>>::block_number() > deadline, Error::<T, I>::LoanNotExpired);
+
+            /* Unlock and move kitty to lender, into the room and reserve held since funding */
+            Loans::<T, I>::remove(kitty_id);
+            LentCount::<T, I>::mutate(&sender, |count| *count = count.saturating_sub(1));
+            Self::move_kitty(kitty_id, &loan.borrower, &sender)?;
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::CollateralClaimed(sender, loan.borrower, kitty_id));
+
+            Ok(().into())
+        }
+        #[pallet::weight(10_000)]
+        pub fn propose_swap(origin: OriginFor<T>, my_kitty: T::KittyIndex, their_kitty: T::KittyIndex, top_up: Option<TopUp<BalanceOf<T, I>>>) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
+
+            /* Check kitties exist */
+            Self::get_kitty(my_kitty).map_err(|_| Error::<T, I>::InvalidKittyId)?;
+            Self::get_kitty(their_kitty).map_err(|_| Error::<T, I>::InvalidKittyId)?;
+
+            /* Only owner can propose, for a kitty of another owner */
+            Self::ensure_owner(&sender, my_kitty)?;
+            ensure!(Self::kitty_owned_by(their_kitty) != Some(sender.clone()), Error::<T, I>::AlreadyOwned);
+            ensure!(!Swaps::<T, I>::contains_key(my_kitty), Error::<T, I>::SwapAlreadyExists);
+
+            /* Locked kitty can not be swapped */
+            Self::ensure_unlocked(my_kitty)?;
+
+            /* Reserve proposer top up until accepted or cancelled */
+            if let Some(TopUp::FromProposer(amount)) = top_up
+            {
+                T::Currency::reserve(&sender, amount).map_err(|_| Error::<T, I>::NotEnoughBalanceReserved)?;
+            }
+
+            Swaps::<T, I>::insert(my_kitty, Swap { proposer: sender.clone(), their_kitty, top_up: top_up.clone() });
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::SwapProposed(sender, my_kitty, their_kitty, top_up));
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn cancel_swap(origin: OriginFor<T>, my_kitty: T::KittyIndex) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
+
+            /* Only proposer can cancel */
+            let swap = Self::swaps(my_kitty).ok_or(Error::<T, I>::SwapNotFound)?;
+            ensure!(swap.proposer == sender, Error::<T, I>::NotOwner);
+
+            /* Remove swap and unreserve top up, post event */
+            Self::clear_swap(my_kitty);
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        #[transactional]
+        pub fn accept_swap(origin: OriginFor<T>, my_kitty: T::KittyIndex) -> DispatchResultWithPostInfo
+        {
+            /* Check signature */
+            let sender = ensure_signed(origin)?;
+
+            /* Only owner of requested kitty can accept */
+            let swap = Self::swaps(my_kitty).ok_or(Error::<T, I>::SwapNotFound)?;
+            Self::ensure_owner(&sender, swap.their_kitty)?;
+
+            /* Locked kitties can not be swapped */
+            Self::ensure_unlocked(my_kitty)?;
+            Self::ensure_unlocked(swap.their_kitty)?;
+
+            /* Pay top up */
+            Swaps::<T, I>::remove(my_kitty);
+            match swap.top_up
+            {
+                Some(TopUp::FromProposer(amount)) => {
+                    T::Currency::repatriate_reserved(&swap.proposer, &sender, amount, BalanceStatus::Free)?;
+                },
+                Some(TopUp::FromCounterparty(amount)) => {
+                    T::Currency::transfer(&sender, &swap.proposer, amount, ExistenceRequirement::KeepAlive)?;
+                },
+                None => {},
+            }
+
+            /* Exchange ownership */
+            Self::do_swap(my_kitty, &swap.proposer, swap.their_kitty, &sender)?;
+
+            /* Post event */
+            Self::deposit_event(Event::<T, I>::SwapAccepted(swap.proposer, sender, my_kitty, swap.their_kitty, swap.top_up));
+
+            Ok(().into())
+        }
+    }
+
+    impl<T: Config<I>, I: 'static> Pallet<T, I>
+    {
+        pub(crate) fn random_value(sender: &T::AccountId) -> [u8; 16]
+        {
+            let payload = (
+                T::Randomness::random_seed(),
+                sender.clone(),
+                <frame_system::Pallet::<T>>::extrinsic_index(),
+            );
+
+            payload.using_encoded(blake2_128)
+        }
+
+        fn get_next_id() -> Result<T::KittyIndex, ()>
+        {
+            let _max_index = T::KittyIndex::max_value();
+
+            match Self::kitty_count()
+            {
+                a if a ==_max_index => Err(()),
+                val => Ok(val),
+            }
+        }
+
+        fn get_kitty(kitty_id: T::KittyIndex) -> Result<Kitty, ()>
+        {
+            match Self::kitties(kitty_id)
+            {
+                Some(kitty) => Ok(kitty),
+                None => Err(()),
+            }
+        }
+
+        fn update_to_new_owner(kitty_id: T::KittyIndex, from: &T::AccountId, to: &T::AccountId) -> Result<(), Error<T, I>>
+        {
+            /* Get old owners kitty vec */
+            let mut from_owned = OwnedKitty::<T, I>::get(from).ok_or(Error::<T, I>::InvalidKittyId)?;
+            /* Get new owners kitty vec */
+            let mut to_owned = OwnedKitty::<T, I>::get(to).unwrap_or(BoundedVec::<T::KittyIndex, T::MaxOwnedAllowed>::default());
+
+			/* Remove kitty from owners kitty vec, add to new owners kitty vec */
+			if let Some(ind) = from_owned.iter().position(|ids| *ids == kitty_id) 
+            {
+				let swap_id = from_owned.swap_remove(ind);
+                to_owned.try_push(swap_id.clone()).map_err(|_| Error::<T, I>::ExceedMaxOwned)?;
+                OwnedKitty::<T, I>::insert(from, from_owned);
+                OwnedKitty::<T, I>::insert(to, to_owned);
+
+                return Ok(());
+			}
+
+            Err(Error::<T, I>::InvalidKittyId)
+        }
+
+        /* Owner, kitty approved operator or owners operator, returns owner */
+        fn ensure_owner_or_approved(who: &T::AccountId, kitty_id: T::KittyIndex) -> Result<T::AccountId, Error<T, I>>
+        {
+            let owner = Self::kitty_owned_by(kitty_id).ok_or(Error::<T, I>::NotOwner)?;
+
+            ensure!(
+                *who == owner
+                    || Self::kitty_approval(kitty_id).as_ref() == Some(who)
+                    || Self::operator_approval(&owner, who),
+                Error::<T, I>::NotOwner
+            );
+
+            Ok(owner)
+        }
+
+        fn ensure_owner(who: &T::AccountId, kitty_id: T::KittyIndex) -> Result<(), Error<T, I>>
+        {
+            let owner = Self::kitty_owned_by(kitty_id).ok_or(Error::<T, I>::InvalidKittyId)?;
+            ensure!(owner == *who, Error::<T, I>::NotOwner);
+
+            Ok(())
+        }
+
+        /* Reserve deposit for bytes on owner, refund deposit of replaced value to its depositor */
+        fn deposit_metadata(owner: &T::AccountId, bytes: usize, old: Option<(T::AccountId, BalanceOf<T, I>)>) -> Result<BalanceOf<T, I>, Error<T, I>>
+        {
+            let deposit = T::DepositPerByte::get().saturating_mul((bytes as u32).into());
+            T::Currency::reserve(owner, deposit).map_err(|_| Error::<T, I>::NotEnoughBalanceReserved)?;
+
+            if let Some((depositor, old_deposit)) = old
+            {
+                T::Currency::unreserve(&depositor, old_deposit);
+            }
+
+            Ok(deposit)
+        }
+
+        pub(crate) fn ensure_unlocked(kitty_id: T::KittyIndex) -> Result<(), Error<T, I>>
+        {
+            ensure!(Self::breeding_locks(kitty_id) == 0, Error::<T, I>::KittyLocked);
+            ensure!(!Loans::<T, I>::contains_key(kitty_id), Error::<T, I>::KittyLocked);
+
+            Ok(())
+        }
+
+        /* Owned kitties and collateral of funded loans stay under max owned */
+        fn ensure_can_own(who: &T::AccountId) -> Result<(), Error<T, I>>
+        {
+            let owned = OwnedKitty::<T, I>::decode_len(who).unwrap_or(0) as u32;
+            ensure!(owned.saturating_add(Self::lent_count(who)) < T::MaxOwnedAllowed::get(), Error::<T, I>::ExceedMaxOwned);
+
+            Ok(())
+        }
+
+        pub(crate) fn do_transfer(kitty_id: T::KittyIndex, from: &T::AccountId, to: &T::AccountId) -> Result<(), Error<T, I>>
+        {
+            /* Can not transfer to self */
+            ensure!(from != to, Error::<T, I>::AlreadyOwned);
+
+            /* Check new owner has room before touching balances */
+            Self::ensure_can_own(to)?;
+
+            /* Reserve on new owner */
+            T::Currency::reserve(to, T::Reserved::get()).map_err(|_| Error::<T, I>::NotEnoughBalanceReserved)?;
+
+            Self::move_kitty(kitty_id, from, to)
+        }
+
+        /* Hand kitty to new owner whose reserve is already held, releasing old owners reserve */
+        fn move_kitty(kitty_id: T::KittyIndex, from: &T::AccountId, to: &T::AccountId) -> Result<(), Error<T, I>>
+        {
+            /* Unreserve on old owner */
+            T::Currency::unreserve(from, T::Reserved::get());
+
+            /* Update owned by map */
+            KittyOwnedBy::<T, I>::insert(kitty_id, to.clone());
+
+            /* Approval, listing and swap are for old owner only */
+            KittyApprovals::<T, I>::remove(kitty_id);
+            SaleList::<T, I>::remove(kitty_id);
+            Self::clear_swap(kitty_id);
+
+            /* Update ownership map */
+            Self::update_to_new_owner(kitty_id, from, to)
+        }
+
+        /* Exchange two kitties in place, owned counts and reserves unchanged */
+        fn do_swap(kitty_id_1: T::KittyIndex, owner_1: &T::AccountId, kitty_id_2: T::KittyIndex, owner_2: &T::AccountId) -> Result<(), Error<T, I>>
+        {
+            ensure!(owner_1 != owner_2, Error::<T, I>::AlreadyOwned);
+
+            for (kitty_id, from, to, other_id) in [(kitty_id_1, owner_1, owner_2, kitty_id_2), (kitty_id_2, owner_2, owner_1, kitty_id_1)]
+            {
+                /* Replace kitty in owners vec, room freed first */
+                let mut owned = OwnedKitty::<T, I>::get(from).ok_or(Error::<T, I>::NotOwner)?;
+                let ind = owned.iter().position(|ids| *ids == kitty_id).ok_or(Error::<T, I>::NotOwner)?;
+                owned.swap_remove(ind);
+                owned.try_push(other_id).map_err(|_| Error::<T, I>::ExceedMaxOwned)?;
+                OwnedKitty::<T, I>::insert(from, owned);
+
+                KittyOwnedBy::<T, I>::insert(kitty_id, to.clone());
+
+                /* Approval, listing and swap are for old owner only */
+                KittyApprovals::<T, I>::remove(kitty_id);
+                SaleList::<T, I>::remove(kitty_id);
+                Self::clear_swap(kitty_id);
+            }
+
+            Ok(())
+        }
+
+        /* Remove swap proposed for kitty, unreserve proposer top up */
+        fn clear_swap(kitty_id: T::KittyIndex)
+        {
+            if let Some(swap) = Swaps::<T, I>::take(kitty_id)
+            {
+                if let Some(TopUp::FromProposer(amount)) = swap.top_up
+                {
+                    T::Currency::unreserve(&swap.proposer, amount);
+                }
+
+                Self::deposit_event(Event::<T, I>::SwapCancelled(swap.proposer, kitty_id));
+            }
+        }
+
+        pub(crate) fn do_burn(kitty_id: T::KittyIndex, owner: &T::AccountId) -> Result<(), Error<T, I>>
+        {
+            /* Listed kitty can not be burned */
+            ensure!(Self::sale_list(kitty_id).is_none(), Error::<T, I>::KittyListed);
+
+            /* Remove kitty from owners kitty vec */
+            let mut owned = OwnedKitty::<T, I>::get(owner).ok_or(Error::<T, I>::InvalidKittyId)?;
+            let ind = owned.iter().position(|ids| *ids == kitty_id).ok_or(Error::<T, I>::InvalidKittyId)?;
+            owned.swap_remove(ind);
+            OwnedKitty::<T, I>::insert(owner, owned);
+
+            /* Remove kitty */
+            Kitties::<T, I>::remove(kitty_id);
+            KittyInfos::<T, I>::remove(kitty_id);
+            KittyOwnedBy::<T, I>::remove(kitty_id);
+            KittyApprovals::<T, I>::remove(kitty_id);
+            SaleList::<T, I>::remove(kitty_id);
+            Self::clear_swap(kitty_id);
+
+            /* Cancel open offers, refund buyers */
+            let buyers: Vec<T::AccountId> = Offers::<T, I>::iter_key_prefix(kitty_id).collect();
+            for buyer in buyers
+            {
+                let offer = Self::remove_offer(kitty_id, &buyer)?;
+                T::Currency::unreserve(&buyer, offer.amount);
+                Self::deposit_event(Event::<T, I>::OfferCancelled(buyer, kitty_id, offer.amount));
+            }
+
+            /* Remove name and attributes, refund deposits */
+            if let Some(name) = KittyNames::<T, I>::take(kitty_id)
+            {
+                T::Currency::unreserve(&name.depositor, name.deposit);
+            }
+            for (_, attribute) in KittyAttributes::<T, I>::drain_prefix(kitty_id)
+            {
+                T::Currency::unreserve(&attribute.depositor, attribute.deposit);
+            }
+            AttributeCount::<T, I>::remove(kitty_id);
+
+            /* Unreserve on owner */
+            T::Currency::unreserve(owner, T::Reserved::get());
+
+            Ok(())
+        }
+
+        /* Split price into marketplace fee, creator royalty and seller part, pay from buyer free or reserved balance */
+        fn pay_for_kitty(kitty_id: T::KittyIndex, buyer: &T::AccountId, seller: &T::AccountId, price: BalanceOf<T, I>, from_reserved: bool) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError>
+        {
+            let fee = T::MarketplaceFee::get() * price;
+
+            /* No royalty when seller is creator or creator account is gone */
+            let creator = Self::kitty_info(kitty_id)
+                .map(|info| info.creator)
+                .filter(|creator| creator != seller && !T::Currency::total_balance(creator).is_zero());
+            let royalty = match creator
+            {
+                Some(_) => T::CreatorRoyalty::get() * price,
+                None => Zero::zero(),
+            };
+
+            let rest = price.saturating_sub(fee).saturating_sub(royalty);
+
+            if from_reserved
+            {
+                let (imbalance, _) = T::Currency::slash_reserved(buyer, fee);
+                T::OnMarketplaceFee::on_unbalanced(imbalance);
+
+                if let Some(creator) = creator
+                {
+                    T::Currency::repatriate_reserved(buyer, &creator, royalty, BalanceStatus::Free)?;
+                }
+
+                T::Currency::repatriate_reserved(buyer, seller, rest, BalanceStatus::Free)?;
+            }
+            else
+            {
+                let imbalance = T::Currency::withdraw(buyer, fee, WithdrawReasons::TRANSFER, ExistenceRequirement::KeepAlive)?;
+                T::OnMarketplaceFee::on_unbalanced(imbalance);
+
+                if let Some(creator) = creator
+                {
+                    T::Currency::transfer(buyer, &creator, royalty, ExistenceRequirement::KeepAlive)?;
+                }
+
+                T::Currency::transfer(buyer, seller, rest, ExistenceRequirement::KeepAlive)?;
+            }
+
+            Ok((fee, royalty))
+        }
+
+        fn remove_offer(kitty_id: T::KittyIndex, buyer: &T::AccountId) -> Result<Offer<BalanceOf<T, I>, T::BlockNumber>, Error<T, I>>
+        {
+            let offer = Offers::<T, I>::take(kitty_id, buyer).ok_or(Error::<T, I>::OfferNotFound)?;
+
+            /* Remove from expiry queue */
+            OfferExpiries::<T, I>::mutate(offer.expiry, |expiring| expiring.retain(|(id, who)| !(*id == kitty_id && who == buyer)));
+
+            Ok(offer)
+        }
+
+        /* Take kitty id, ownership and reserve, without dna */
+        fn allocate(owner: &T::AccountId) -> Result<T::KittyIndex, Error<T, I>>
+        {
+            /* Get kitty id */
+            let kitty_id = Self::get_next_id().map_err(|_| Error::<T, I>::InvalidKittyId)?;
+
+            /* Check owner has room before touching balances */
+            Self::ensure_can_own(owner)?;
+            let mut owned_vec = OwnedKitty::<T, I>::get(&owner).unwrap_or(BoundedVec::<T::KittyIndex, T::MaxOwnedAllowed>::default());
+            owned_vec.try_push(kitty_id.clone()).map_err(|_| Error::<T, I>::ExceedMaxOwned)?;
+
+            /* Get reversed mount */
+            let reserved = T::Reserved::get();
+
+            /* Reserve on owner */
+            T::Currency::reserve(&owner, reserved).map_err(|_| Error::<T, I>::NotEnoughBalanceReserved)?;
+
+            /* Save kitty info */
+            KittyInfos::<T, I>::insert(kitty_id, KittyInfo { creator: owner.clone() });
+            
+            /* Save kitty with owner */
+            KittyOwnedBy::<T, I>::insert(kitty_id, owner.clone());
+            
+            /* Update ownership map */
+            OwnedKitty::<T, I>::insert(owner, owned_vec);
+            
+            /* Update new kitty Id */
+            KittyCount::<T, I>::set(kitty_id + 1u32.into());
+
+            Ok(kitty_id)
+        }
+
+        /* Mint kitty with known dna */
+        fn mint(owner: &T::AccountId, dna: [u8; 16]) -> Result<(T::AccountId, T::KittyIndex, Kitty), Error<T, I>>
+        {
+            /* Take kitty id with reserve */
+            let kitty_id = Self::allocate(owner)?;
+
+            /* New kitty from dna */
+            let new_kitty = Kitty(dna);
+            
+            /* Save kitty to storage */
+            Kitties::<T, I>::insert(kitty_id, new_kitty.clone());
+            
+            Ok((owner.clone(), kitty_id, new_kitty))
+        }
+
+        /* Commit a kitty on create/breed, dna is revealed after RevealDelay blocks */
+        pub(crate) fn commit(owner: &T::AccountId, seed: [u8; 16], parents: Option<((T::KittyIndex, Kitty), (T::KittyIndex, Kitty))>) -> Result<T::KittyIndex, Error<T, I>>
+        {
+            let mut pending = PendingKitty { seed, parents: None, locked: (None, None) };
+            let delay = T::RevealDelay::get();
+
+            /* Parents, owned by breeder, are locked until reveal */
+            if let Some(((kitty_id_1, _), (kitty_id_2, _))) = parents.as_ref().filter(|_| !delay.is_zero())
+            {
+                pending.locked = (Some(*kitty_id_1), Some(*kitty_id_2));
+            }
+            pending.parents = parents.map(|((_, kitty_1), (_, kitty_2))| (kitty_1, kitty_2));
+
+            /* No delay, reveal in the same call */
+            if delay.is_zero()
+            {
+                let kitty_id = Self::allocate(owner)?;
+                Self::reveal(kitty_id, pending);
+
+                return Ok(kitty_id);
+            }
+
+            /* Check reveal queue has room before touching balances */
+            let reveal_at = <frame_system::Pallet::<T>>::block_number().saturating_add(delay);
+            ensure!((RevealQueue::<T, I>::decode_len(reveal_at).unwrap_or(0) as u32) < T::MaxPendingReveals::get(), Error::<T, I>::TooManyPendingReveals);
+
+            /* Take kitty id with reserve */
+            let kitty_id = Self::allocate(owner)?;
+
+            /* Lock parents, listing cleared */
+            for parent_id in [pending.locked.0, pending.locked.1].into_iter().flatten()
+            {
+                BreedingLocks::<T, I>::mutate(parent_id, |locks| *locks = locks.saturating_add(1));
+
+                if SaleList::<T, I>::take(parent_id).is_some()
+                {
+                    Self::deposit_event(Event::<T, I>::KittyUnlisted(owner.clone(), parent_id));
+                }
+            }
+
+            /* Queue for reveal */
+            RevealQueue::<T, I>::try_append(reveal_at, kitty_id).map_err(|_| Error::<T, I>::TooManyPendingReveals)?;
+            PendingKitties::<T, I>::insert(kitty_id, pending);
+
+            Self::deposit_event(Event::<T, I>::KittyCommitted(owner.clone(), kitty_id, reveal_at));
+
+            Ok(kitty_id)
+        }
+
+        /* Finalise dna from commit seed and randomness known now */
+        fn reveal(kitty_id: T::KittyIndex, pending: PendingKitty<T::KittyIndex>)
+        {
+            /* Unlock parents */
+            for parent_id in [pending.locked.0, pending.locked.1].into_iter().flatten()
+            {
+                BreedingLocks::<T, I>::mutate_exists(parent_id, |locks| {
+                    *locks = locks.and_then(|locks| locks.checked_sub(1)).filter(|locks| *locks > 0);
+                });
+            }
+
+            let (random, _) = T::Randomness::random(&(b"kitties/reveal", kitty_id, pending.seed).encode());
+            let selector = (pending.seed, random).using_encoded(blake2_128);
+
+            let owner = match Self::kitty_owned_by(kitty_id)
+            {
+                Some(owner) => owner,
+                None => return,
+            };
+
+            match pending.parents
+            {
+                /* Use random selector generating bread dna */
+                Some((kitty_1, kitty_2)) =>
+                {
+                    let mut dna = [0u8; 16];
+                    for i in 0..kitty_1.0.len(){
+                        dna[i] = (kitty_1.0[i] & selector[i]) | (kitty_2.0[i] & !selector[i]);
+                    }
+
+                    let new_kitty = Kitty(dna);
+                    Kitties::<T, I>::insert(kitty_id, new_kitty.clone());
+                    Self::deposit_event(Event::<T, I>::KittyBreed(owner, kitty_id, new_kitty));
+                },
+                None =>
+                {
+                    let new_kitty = Kitty(selector);
+                    Kitties::<T, I>::insert(kitty_id, new_kitty.clone());
+                    Self::deposit_event(Event::<T, I>::KittyCreated(owner, kitty_id, new_kitty));
+                },
+            }
+        }
+    }
+
+    /* Queries for runtime api */
+    impl<T: Config<I>, I: 'static> Pallet<T, I>
+    {
+        /* Page through kitties of owner from position start in the owned list */
+        pub fn kitties_of(owner: T::AccountId, start: u32, limit: u32) -> Page<T::KittyIndex, u32>
+        {
+            let owned = Self::owned_kitty(owner).map(|owned| owned.into_inner()).unwrap_or_default();
+            let end = start.saturating_add(limit.min(MAX_PAGE_LIMIT)).min(owned.len() as u32);
+            let items = owned.get(start as usize..end as usize).map(|page| page.to_vec()).unwrap_or_default();
+            let next = if (end as usize) < owned.len() { Some(end) } else { None };
+
+            Page { items, next }
+        }
+
+        pub fn kitty_details(kitty_id: T::KittyIndex) -> Option<KittyDetails<T::AccountId, BalanceOf<T, I>>>
+        {
+            let owner = Self::kitty_owned_by(kitty_id)?;
+
+            Some(KittyDetails {
+                dna: Self::kitties(kitty_id),
+                owner,
+                creator: Self::kitty_info(kitty_id).map(|info| info.creator),
+                name: Self::kitty_name(kitty_id).map(|name| name.value.into_inner()),
+                price: Self::sale_list(kitty_id),
+            })
+        }
+
+        /* Page through kitties from start id, skip burned ids */
+        pub fn all_kitties(start: T::KittyIndex, limit: u32) -> Page<(T::KittyIndex, KittyDetails<T::AccountId, BalanceOf<T, I>>), T::KittyIndex>
+        {
+            Self::page(start, limit, |kitty_id| Self::kitty_details(kitty_id))
+        }
+
+        /* Page through kitties for sale from start id */
+        pub fn on_sale(start: T::KittyIndex, limit: u32) -> Page<(T::KittyIndex, BalanceOf<T, I>), T::KittyIndex>
+        {
+            Self::page(start, limit, |kitty_id| Self::sale_list(kitty_id))
+        }
+
+        /* Check up to limit ids from start, so sparse pages come back short with a next cursor */
+        fn page<V>(start: T::KittyIndex, limit: u32, get: impl Fn(T::KittyIndex) -> Option<V>) -> Page<(T::KittyIndex, V), T::KittyIndex>
+        {
+            let count = Self::kitty_count();
+            let mut items = Vec::new();
+            let mut kitty_id = start;
+            let mut checked = 0;
+
+            while kitty_id < count && checked < limit.min(MAX_PAGE_LIMIT)
+            {
+                if let Some(value) = get(kitty_id)
+                {
+                    items.push((kitty_id, value));
+                }
+
+                kitty_id += 1u32.into();
+                checked += 1;
+            }
+
+            let next = if kitty_id < count { Some(kitty_id) } else { None };
+            Page { items, next }
         }
     }
-}
\ No newline at end of file
+}
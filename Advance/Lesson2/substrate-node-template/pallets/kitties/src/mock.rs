@@ -1,9 +1,10 @@
 use crate as pallet_kitties;
-use frame_support::{traits::{ConstU16, ConstU32, ConstU64, ConstU128}, parameter_types};
+use frame_support::{instances::Instance1, traits::{ConstU16, ConstU32, ConstU64, ConstU128, Currency, GenesisBuild, OnInitialize, OnUnbalanced, Randomness}, parameter_types};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	traits::{BlakeTwo256, Hash, IdentityLookup},
+	Permill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -18,9 +19,9 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		Kitties: pallet_kitties::{Pallet, Call, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		Randomness: pallet_randomness_collective_flip::{Pallet, Storage},
+		Kitties: pallet_kitties::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Dogs: pallet_kitties::<Instance1>::{Pallet, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
@@ -64,20 +65,80 @@ impl pallet_balances::Config for Test {
 	type WeightInfo = pallet_balances::weights::SubstrateWeight<Test>;
 }
 
-impl pallet_randomness_collective_flip::Config for Test {}
+// Deterministic randomness from subject and block number.
+pub struct TestRandomness;
+impl Randomness<H256, u64> for TestRandomness {
+	fn random(subject: &[u8]) -> (H256, u64) {
+		let block_number = System::block_number();
+		(BlakeTwo256::hash_of(&(subject, block_number)), block_number)
+	}
+}
 
 parameter_types! {
 	pub const Reserved: u128 = 10_000;
 	pub const MaxOwnedAllowed: u32 = 3;
+	pub const MaxExpiringOffers: u32 = 2;
+	pub const MaxOfferDuration: u64 = 20;
+	pub const MinimumPrice: u128 = 1_000;
+	pub const StringLimit: u32 = 8;
+	pub const MaxAttributes: u32 = 2;
+	pub const DepositPerByte: u128 = 10;
+	pub const MaxPendingReveals: u32 = 2;
+	pub static RevealDelay: u64 = 0;
+	pub const MarketplaceFee: Permill = Permill::from_percent(5);
+	pub const CreatorRoyalty: Permill = Permill::from_percent(10);
+	pub const TreasuryAccount: u64 = 99;
+	pub const DogsReserved: u128 = 5_000;
+	pub const DogsMaxOwnedAllowed: u32 = 1;
+}
+
+// Deposit marketplace fee to treasury account.
+pub struct ToTreasury;
+impl OnUnbalanced<pallet_balances::NegativeImbalance<Test>> for ToTreasury {
+	fn on_nonzero_unbalanced(amount: pallet_balances::NegativeImbalance<Test>) {
+		Balances::resolve_creating(&TreasuryAccount::get(), amount);
+	}
 }
 
 impl pallet_kitties::Config for Test {
 	type Event = Event;
-	type Randomness = Randomness;
+	type Randomness = TestRandomness;
+	type RevealDelay = RevealDelay;
+	type MaxPendingReveals = MaxPendingReveals;
 	type KittyIndex = u32;
 	type Currency = Balances;
 	type Reserved = Reserved;
 	type MaxOwnedAllowed = MaxOwnedAllowed;
+	type MinimumPrice = MinimumPrice;
+	type StringLimit = StringLimit;
+	type MaxAttributes = MaxAttributes;
+	type DepositPerByte = DepositPerByte;
+	type MaxExpiringOffers = MaxExpiringOffers;
+	type MaxOfferDuration = MaxOfferDuration;
+	type MarketplaceFee = MarketplaceFee;
+	type CreatorRoyalty = CreatorRoyalty;
+	type OnMarketplaceFee = ToTreasury;
+}
+
+// Second collection with its own deposit and ownership limit.
+impl pallet_kitties::Config<Instance1> for Test {
+	type Event = Event;
+	type Randomness = TestRandomness;
+	type RevealDelay = RevealDelay;
+	type MaxPendingReveals = MaxPendingReveals;
+	type KittyIndex = u32;
+	type Currency = Balances;
+	type Reserved = DogsReserved;
+	type MaxOwnedAllowed = DogsMaxOwnedAllowed;
+	type MinimumPrice = MinimumPrice;
+	type StringLimit = StringLimit;
+	type MaxAttributes = MaxAttributes;
+	type DepositPerByte = DepositPerByte;
+	type MaxExpiringOffers = MaxExpiringOffers;
+	type MaxOfferDuration = MaxOfferDuration;
+	type MarketplaceFee = MarketplaceFee;
+	type CreatorRoyalty = CreatorRoyalty;
+	type OnMarketplaceFee = ToTreasury;
 }
 
 #[macro_export]
@@ -91,11 +152,24 @@ macro_rules! assert_has_event
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
+	new_test_ext_with_kitties(vec![])
+}
+
+// Build genesis storage with founder kitties.
+pub fn new_test_ext_with_kitties(kitties: Vec<(u64, [u8; 16])>) -> sp_io::TestExternalities {
 	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 
 	pallet_balances::GenesisConfig::<Test> 
 	{
-		balances: vec![(1, 100_000_000_000), (2, 100_000_000_000), (3, 9999), (4, 20_000)],
+		balances: vec![(1, 100_000_000_000), (2, 100_000_000_000), (3, 9999), (4, 20_000), (TreasuryAccount::get(), 1_000)],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
+	pallet_kitties::GenesisConfig::<Test> 
+	{
+		kitties,
+		phantom: Default::default(),
 	}
 	.assimilate_storage(&mut storage)
 	.unwrap();
@@ -104,3 +178,12 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	ext.execute_with(|| System::set_block_number(1));
 	ext
 }
+
+// Run blocks until target block number, with kitties hooks.
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Kitties::on_initialize(System::block_number());
+		Dogs::on_initialize(System::block_number());
+	}
+}
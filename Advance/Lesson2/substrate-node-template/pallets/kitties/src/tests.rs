@@ -1,5 +1,5 @@
-use crate::mock::{Event as TestEvent, new_test_ext, Kitties as KittiesMod, Origin, System, Test};
-use frame_support::{assert_noop, assert_ok, bounded_vec, assert_err};
+use crate::mock::{Event as TestEvent, new_test_ext, new_test_ext_with_kitties, run_to_block, Balances, Dogs, Kitties as KittiesMod, MaxOfferDuration, MinimumPrice, Origin, RevealDelay, System, Test, TreasuryAccount};
+use frame_support::{assert_noop, assert_ok, bounded_vec, assert_err, instances::Instance1, traits::tokens::nonfungible};
 // use super::*;
 use crate::*;
 
@@ -23,6 +23,7 @@ fn create_works()
 		assert_eq!(KittyCount::<Test>::get(), 1);
 		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_1));
 		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_1), Some(bounded_vec![0]));
+		assert_eq!(KittyInfos::<Test>::get(0), Some(KittyInfo { creator: ACCOUNT_ID_1 }));
 		
 		assert_has_event!(Event::<Test>::KittyCreated(ACCOUNT_ID_1, 0, Kitties::<Test>::get(0).unwrap()));
 	});
@@ -48,23 +49,135 @@ fn create_failed_exceed_max_owned()
 	});
 }
 
+#[test]
+fn create_works_with_reveal_delay()
+{
+	new_test_ext().execute_with(|| {
+		RevealDelay::set(&3);
+
+		/* Kitty owned and reserved at commit, dna unknown */
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_eq!(KittyCount::<Test>::get(), 1);
+		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_1));
+		assert_eq!(Kitties::<Test>::get(0), None);
+		assert_eq!(RevealQueue::<Test>::get(4).into_inner(), vec![0]);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 10_000);
+		assert_has_event!(Event::<Test>::KittyCommitted(ACCOUNT_ID_1, 0, 4));
+
+		/* Pending kitty can not be transferred */
+		assert_noop!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2), Error::<Test>::InvalidKittyId);
+
+		run_to_block(3);
+		assert_eq!(Kitties::<Test>::get(0), None);
+
+		/* Dna revealed */
+		run_to_block(4);
+		assert_eq!(PendingKitties::<Test>::get(0), None);
+		assert!(RevealQueue::<Test>::get(4).is_empty());
+		assert_has_event!(Event::<Test>::KittyCreated(ACCOUNT_ID_1, 0, Kitties::<Test>::get(0).unwrap()));
+	});
+}
+
+#[test]
+fn create_failed_too_many_pending_reveals()
+{
+	new_test_ext().execute_with(|| {
+		RevealDelay::set(&3);
+
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_noop!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)), Error::<Test>::TooManyPendingReveals);
+	});
+}
+
 /****************************************BREED***************************************************/
 #[test]
 fn breed_works()
 {
 	new_test_ext().execute_with(|| {
 		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
-		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
 		assert_ok!(KittiesMod::breed(Origin::signed(ACCOUNT_ID_1), 0, 1));
 		assert_eq!(KittyCount::<Test>::get(), 3);
 		assert_eq!(KittyOwnedBy::<Test>::get(2), Some(ACCOUNT_ID_1));
-		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_1), Some(bounded_vec![0, 2]));
-		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_2), Some(bounded_vec![1]));
+		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_1), Some(bounded_vec![0, 1, 2]));
+		assert_eq!(KittyInfos::<Test>::get(2), Some(KittyInfo { creator: ACCOUNT_ID_1 }));
 		
 		assert_has_event!(Event::<Test>::KittyBreed(ACCOUNT_ID_1, 2, Kitties::<Test>::get(2).unwrap()));
 	});
 }
 
+#[test]
+fn breed_works_with_reveal_delay()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+
+		RevealDelay::set(&2);
+		assert_ok!(KittiesMod::breed(Origin::signed(ACCOUNT_ID_1), 0, 1));
+		assert_eq!(KittyOwnedBy::<Test>::get(2), Some(ACCOUNT_ID_1));
+		assert_eq!(Kitties::<Test>::get(2), None);
+		assert_has_event!(Event::<Test>::KittyCommitted(ACCOUNT_ID_1, 2, 3));
+
+		/* Pending kitty can not breed */
+		assert_noop!(KittiesMod::breed(Origin::signed(ACCOUNT_ID_1), 0, 2), Error::<Test>::InvalidKittyId);
+
+		run_to_block(3);
+		let parent_1 = Kitties::<Test>::get(0).unwrap();
+		let parent_2 = Kitties::<Test>::get(1).unwrap();
+		let child = Kitties::<Test>::get(2).unwrap();
+
+		/* Each gene comes from one of parents */
+		for i in 0..child.0.len() {
+			assert_eq!(child.0[i] & !(parent_1.0[i] | parent_2.0[i]), 0);
+		}
+		assert_has_event!(Event::<Test>::KittyBreed(ACCOUNT_ID_1, 2, child));
+	});
+}
+
+#[test]
+fn breed_locks_parents_until_reveal()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 2_000));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 1, 2_000));
+
+		RevealDelay::set(&2);
+		assert_ok!(KittiesMod::breed(Origin::signed(ACCOUNT_ID_1), 0, 1));
+
+		/* Both parents are locked and unlisted */
+		assert_eq!(BreedingLocks::<Test>::get(0), 1);
+		assert_eq!(BreedingLocks::<Test>::get(1), 1);
+		assert_eq!(SaleList::<Test>::get(0), None);
+		assert_eq!(SaleList::<Test>::get(1), None);
+		assert_has_event!(Event::<Test>::KittyUnlisted(ACCOUNT_ID_1, 0));
+		assert_has_event!(Event::<Test>::KittyUnlisted(ACCOUNT_ID_1, 1));
+
+		assert_noop!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 2_000), Error::<Test>::KittyLocked);
+		assert_noop!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_1), 1, ACCOUNT_ID_2), Error::<Test>::KittyLocked);
+		assert_noop!(KittiesMod::burn(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::KittyLocked);
+
+		run_to_block(3);
+		assert_eq!(BreedingLocks::<Test>::get(0), 0);
+		assert_eq!(BreedingLocks::<Test>::get(1), 0);
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 2_000));
+	});
+}
+
+#[test]
+fn breed_failed_not_owner()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_noop!(KittiesMod::breed(Origin::signed(ACCOUNT_ID_1), 0, 1), Error::<Test>::NotOwner);
+		assert_noop!(KittiesMod::breed(Origin::signed(ACCOUNT_ID_1), 1, 0), Error::<Test>::NotOwner);
+	})
+}
+
 #[test]
 fn breed_failed_same_kitty_id()
 {
@@ -89,9 +202,12 @@ fn breed_failed_invalid_kitty_id()
 fn breed_failed_not_enough_balance_reserved()
 {
 	new_test_ext().execute_with(|| {
-		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
 		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
-		assert_noop!(KittiesMod::breed(Origin::signed(ACCOUNT_ID_3), 0, 1), Error::<Test>::NotEnoughBalanceReserved);
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+
+		/* Leave less free balance than a reserve */
+		assert_ok!(Balances::transfer(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_1, Balances::free_balance(ACCOUNT_ID_2) - 5_000));
+		assert_noop!(KittiesMod::breed(Origin::signed(ACCOUNT_ID_2), 0, 1), Error::<Test>::NotEnoughBalanceReserved);
 	})
 }
 
@@ -116,6 +232,7 @@ fn transfer_works()
 		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_1));
 		assert_ok!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2));
 		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_2));
+		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_1), Some(bounded_vec![]));
 		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_2), Some(bounded_vec![0]));
 		
 		assert_has_event!(Event::<Test>::KittyTransferred(ACCOUNT_ID_1, ACCOUNT_ID_2, 0));
@@ -169,10 +286,10 @@ fn sell_works()
 		let price: u128 = 2_000;
 
 		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
-		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, Some(price)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, price));
 		assert_eq!(SaleList::<Test>::get(0), Some(price));
 
-		assert_has_event!(Event::<Test>::KittyOnSale(ACCOUNT_ID_1, 0, Some(price)));
+		assert_has_event!(Event::<Test>::KittyOnSale(ACCOUNT_ID_1, 0, price));
 	});
 }
 
@@ -183,10 +300,74 @@ fn sell_failed_not_owner()
 
 	new_test_ext().execute_with(|| {
 		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
-		assert_noop!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_2), 0, Some(price)), Error::<Test>::NotOwner);
+		assert_noop!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_2), 0, price), Error::<Test>::NotOwner);
 	})
 }
 
+#[test]
+fn sell_failed_invalid_kitty_id()
+{
+	new_test_ext().execute_with(|| {
+		assert_noop!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 2_000), Error::<Test>::InvalidKittyId);
+	});
+}
+
+#[test]
+fn sell_failed_price_too_low()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 999), Error::<Test>::PriceTooLow);
+	});
+}
+
+#[test]
+fn sell_cleared_on_transfer()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 2_000));
+		assert_ok!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2));
+		assert_eq!(SaleList::<Test>::get(0), None);
+
+		/* Old price can not be used to buy from new owner */
+		assert_noop!(KittiesMod::buy(Origin::signed(ACCOUNT_ID_4), 0), Error::<Test>::NotForSale);
+	});
+}
+
+/****************************************UNLIST***************************************************/
+#[test]
+fn unlist_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 2_000));
+		assert_ok!(KittiesMod::unlist(Origin::signed(ACCOUNT_ID_1), 0));
+		assert_eq!(SaleList::<Test>::get(0), None);
+
+		assert_has_event!(Event::<Test>::KittyUnlisted(ACCOUNT_ID_1, 0));
+	});
+}
+
+#[test]
+fn unlist_failed_not_owner()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 2_000));
+		assert_noop!(KittiesMod::unlist(Origin::signed(ACCOUNT_ID_2), 0), Error::<Test>::NotOwner);
+	});
+}
+
+#[test]
+fn unlist_failed_not_for_sale()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::unlist(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::NotForSale);
+	});
+}
+
 
 /****************************************BUY***************************************************/
 #[test]
@@ -196,12 +377,38 @@ fn buy_works()
 		let price: u128 = 2_000;
 	
 		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
-		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, Some(price)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, price));
 		assert_ok!(KittiesMod::buy(Origin::signed(ACCOUNT_ID_2), 0));
 		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_2));
 		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_2), Some(bounded_vec![0]));
+
+		/* Seller is creator, no royalty, 5% fee to treasury */
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 100_000_000_000 + 1_900);
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()), 1_000 + 100);
 	
-		assert_has_event!(Event::<Test>::KittySaled(ACCOUNT_ID_1, ACCOUNT_ID_2, 0, Some(price)));
+		assert_has_event!(Event::<Test>::KittySaled(ACCOUNT_ID_1, ACCOUNT_ID_2, 0, price, 100, 0));
+	});
+}
+
+#[test]
+fn buy_works_with_creator_royalty()
+{
+	new_test_ext().execute_with(|| {
+		let price: u128 = 2_000;
+
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_2), 0, price));
+		assert_ok!(KittiesMod::buy(Origin::signed(ACCOUNT_ID_4), 0));
+		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_4));
+
+		/* 5% fee to treasury, 10% royalty to creator, rest to seller */
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()), 1_000 + 100);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 100_000_000_000 + 200);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 100_000_000_000 + 1_700);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_4), 20_000 - 10_000 - price);
+
+		assert_has_event!(Event::<Test>::KittySaled(ACCOUNT_ID_2, ACCOUNT_ID_4, 0, price, 100, 200));
 	});
 }
 
@@ -212,7 +419,7 @@ fn buy_failed_already_owned()
 		let price: u128 = 2_000;
 
 		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
-		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, Some(price)));	
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, price));	
 		assert_noop!(KittiesMod::buy(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::AlreadyOwned);
 	});
 }
@@ -233,11 +440,21 @@ fn buy_failed_not_enought_balance_buy()
 		let price:u128 = 11_000;
 		
 		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
-		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, Some(price)));	
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, price));	
 		assert_noop!(KittiesMod::buy(Origin::signed(ACCOUNT_ID_4), 0), Error::<Test>::NotEnoughBalanceBuy);
 	});
 }
 
+#[test]
+fn buy_failed_price_near_max_balance()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, u128::MAX));
+		assert_noop!(KittiesMod::buy(Origin::signed(ACCOUNT_ID_2), 0), Error::<Test>::NotEnoughBalanceBuy);
+	});
+}
+
 #[test]
 fn buy_failed_exceed_max_owned()
 {
@@ -249,8 +466,1002 @@ fn buy_failed_exceed_max_owned()
 		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
 		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
 
-		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, Some(price)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, price));
 		
 		assert_err!(KittiesMod::buy(Origin::signed(ACCOUNT_ID_2), 0), Error::<Test>::ExceedMaxOwned);
 	});
-}
\ No newline at end of file
+}
+
+/****************************************BURN***************************************************/
+#[test]
+fn burn_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::approve(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 30_000);
+
+		assert_ok!(KittiesMod::burn(Origin::signed(ACCOUNT_ID_1), 0));
+		assert_eq!(Kitties::<Test>::get(0), None);
+		assert_eq!(KittyInfos::<Test>::get(0), None);
+		assert_eq!(KittyOwnedBy::<Test>::get(0), None);
+		assert_eq!(KittyApprovals::<Test>::get(0), None);
+		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_1), Some(bounded_vec![2, 1]));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 20_000);
+		assert_has_event!(Event::<Test>::KittyBurned(ACCOUNT_ID_1, 0));
+
+		/* Room for a new kitty */
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_1), Some(bounded_vec![2, 1, 3]));
+	});
+}
+
+#[test]
+fn burn_failed_invalid_kitty_id()
+{
+	new_test_ext().execute_with(|| {
+		assert_noop!(KittiesMod::burn(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::InvalidKittyId);
+	});
+}
+
+#[test]
+fn burn_failed_not_owner()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::burn(Origin::signed(ACCOUNT_ID_2), 0), Error::<Test>::NotOwner);
+	});
+}
+
+#[test]
+fn burn_failed_kitty_listed()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 2_000));
+		assert_noop!(KittiesMod::burn(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::KittyListed);
+	});
+}
+
+/****************************************APPROVAL***************************************************/
+#[test]
+fn approve_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::approve(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2));
+		assert_eq!(KittyApprovals::<Test>::get(0), Some(ACCOUNT_ID_2));
+
+		assert_has_event!(Event::<Test>::KittyApproved(ACCOUNT_ID_1, ACCOUNT_ID_2, 0));
+	});
+}
+
+#[test]
+fn approve_failed_not_owner()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::approve(Origin::signed(ACCOUNT_ID_2), 0, ACCOUNT_ID_2), Error::<Test>::NotOwner);
+	});
+}
+
+#[test]
+fn cancel_approval_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::approve(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2));
+		assert_ok!(KittiesMod::cancel_approval(Origin::signed(ACCOUNT_ID_1), 0));
+		assert_eq!(KittyApprovals::<Test>::get(0), None);
+		assert_noop!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_2), 0, ACCOUNT_ID_2), Error::<Test>::NotOwner);
+
+		assert_has_event!(Event::<Test>::KittyApprovalCancelled(ACCOUNT_ID_1, 0));
+	});
+}
+
+#[test]
+fn cancel_approval_failed_not_approved()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::cancel_approval(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::NotApproved);
+	});
+}
+
+#[test]
+fn transfer_works_by_approved_operator()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::approve(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2));
+		assert_ok!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_2), 0, ACCOUNT_ID_4));
+		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_4));
+
+		/* Approval cleared on transfer */
+		assert_eq!(KittyApprovals::<Test>::get(0), None);
+		assert_noop!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_2), 0, ACCOUNT_ID_1), Error::<Test>::NotOwner);
+
+		assert_has_event!(Event::<Test>::KittyTransferred(ACCOUNT_ID_1, ACCOUNT_ID_4, 0));
+	});
+}
+
+#[test]
+fn set_approval_for_all_works()
+{
+	new_test_ext().execute_with(|| {
+		let price: u128 = 2_000;
+
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::set_approval_for_all(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_2, true));
+		assert!(OperatorApprovals::<Test>::get(ACCOUNT_ID_1, ACCOUNT_ID_2));
+		assert_has_event!(Event::<Test>::ApprovalForAll(ACCOUNT_ID_1, ACCOUNT_ID_2, true));
+
+		/* Operator sells and transfers on owners behalf */
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_2), 0, price));
+		assert_eq!(SaleList::<Test>::get(0), Some(price));
+		assert_has_event!(Event::<Test>::KittyOnSale(ACCOUNT_ID_1, 0, price));
+		assert_ok!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_2), 1, ACCOUNT_ID_4));
+		assert_eq!(KittyOwnedBy::<Test>::get(1), Some(ACCOUNT_ID_4));
+
+		/* Revoked operator */
+		assert_ok!(KittiesMod::set_approval_for_all(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_2, false));
+		assert!(!OperatorApprovals::<Test>::get(ACCOUNT_ID_1, ACCOUNT_ID_2));
+		assert_noop!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_2), 0, ACCOUNT_ID_4), Error::<Test>::NotOwner);
+	});
+}
+
+/****************************************OFFER***************************************************/
+#[test]
+fn make_offer_works()
+{
+	new_test_ext().execute_with(|| {
+		let amount: u128 = 5_000;
+
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, amount, 10));
+		assert_eq!(Offers::<Test>::get(0, ACCOUNT_ID_2), Some(Offer { amount, expiry: 10 }));
+		assert_eq!(OfferExpiries::<Test>::get(10).into_inner(), vec![(0, ACCOUNT_ID_2)]);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), amount);
+
+		assert_has_event!(Event::<Test>::OfferMade(ACCOUNT_ID_2, 0, amount, 10));
+	});
+}
+
+#[test]
+fn make_offer_failed_already_owned()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_1), 0, 5_000, 10), Error::<Test>::AlreadyOwned);
+	});
+}
+
+#[test]
+fn make_offer_failed_invalid_expiry()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, 5_000, 1), Error::<Test>::InvalidExpiry);
+
+		/* Open at most MaxOfferDuration blocks */
+		assert_noop!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, 5_000, 1 + MaxOfferDuration::get() + 1), Error::<Test>::InvalidExpiry);
+		assert_ok!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, 5_000, 1 + MaxOfferDuration::get()));
+	});
+}
+
+#[test]
+fn make_offer_failed_price_too_low()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, MinimumPrice::get() - 1, 10), Error::<Test>::PriceTooLow);
+	});
+}
+
+#[test]
+fn make_offer_failed_offer_already_exists()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, 5_000, 10));
+		assert_noop!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, 6_000, 10), Error::<Test>::OfferAlreadyExists);
+	});
+}
+
+#[test]
+fn make_offer_failed_not_enough_balance_reserved()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_3), 0, 10_000, 10), Error::<Test>::NotEnoughBalanceReserved);
+	});
+}
+
+#[test]
+fn make_offer_failed_too_many_expiring_offers()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, 1_000, 10));
+		assert_ok!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_4), 0, 1_000, 10));
+		assert_noop!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_3), 0, 1_000, 10), Error::<Test>::TooManyExpiringOffers);
+	});
+}
+
+#[test]
+fn cancel_offer_works()
+{
+	new_test_ext().execute_with(|| {
+		let amount: u128 = 5_000;
+
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, amount, 10));
+		assert_ok!(KittiesMod::cancel_offer(Origin::signed(ACCOUNT_ID_2), 0));
+		assert_eq!(Offers::<Test>::get(0, ACCOUNT_ID_2), None);
+		assert!(OfferExpiries::<Test>::get(10).is_empty());
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 0);
+
+		assert_has_event!(Event::<Test>::OfferCancelled(ACCOUNT_ID_2, 0, amount));
+	});
+}
+
+#[test]
+fn cancel_offer_failed_offer_not_found()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::cancel_offer(Origin::signed(ACCOUNT_ID_2), 0), Error::<Test>::OfferNotFound);
+	});
+}
+
+#[test]
+fn accept_offer_works()
+{
+	new_test_ext().execute_with(|| {
+		let amount: u128 = 5_000;
+
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 8_000));
+		assert_ok!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, amount, 10));
+		assert_ok!(KittiesMod::accept_offer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2));
+
+		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_2));
+		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_1), Some(bounded_vec![]));
+		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_2), Some(bounded_vec![0]));
+		assert_eq!(SaleList::<Test>::get(0), None);
+		assert_eq!(Offers::<Test>::get(0, ACCOUNT_ID_2), None);
+		assert!(OfferExpiries::<Test>::get(10).is_empty());
+
+		/* Offer amount paid to owner less 5% fee, kitty reserve moved to buyer */
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 0);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 100_000_000_000 + amount - 250);
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()), 1_000 + 250);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 10_000);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 100_000_000_000 - 10_000 - amount);
+
+		assert_has_event!(Event::<Test>::OfferAccepted(ACCOUNT_ID_1, ACCOUNT_ID_2, 0, amount, 250, 0));
+	});
+}
+
+#[test]
+fn accept_offer_failed_not_owner()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, 5_000, 10));
+		assert_noop!(KittiesMod::accept_offer(Origin::signed(ACCOUNT_ID_2), 0, ACCOUNT_ID_2), Error::<Test>::NotOwner);
+	});
+}
+
+#[test]
+fn accept_offer_failed_offer_not_found()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::accept_offer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2), Error::<Test>::OfferNotFound);
+	});
+}
+
+#[test]
+fn offer_expired_unreserved()
+{
+	new_test_ext().execute_with(|| {
+		let amount: u128 = 5_000;
+
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, amount, 3));
+
+		run_to_block(2);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), amount);
+
+		run_to_block(3);
+		assert_eq!(Offers::<Test>::get(0, ACCOUNT_ID_2), None);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 0);
+		assert_noop!(KittiesMod::accept_offer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2), Error::<Test>::OfferNotFound);
+
+		assert_has_event!(Event::<Test>::OfferExpired(ACCOUNT_ID_2, 0, amount));
+	});
+}
+
+/****************************************METADATA***************************************************/
+#[test]
+fn set_name_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::set_name(Origin::signed(ACCOUNT_ID_1), 0, b"Tom".to_vec()));
+		assert_eq!(KittiesMod::kitty_name(0).unwrap().value.into_inner(), b"Tom".to_vec());
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 10_000 + 30);
+		assert_has_event!(Event::<Test>::KittyNamed(ACCOUNT_ID_1, 0, bounded_vec![b'T', b'o', b'm']));
+
+		/* Rename charges for new length only */
+		assert_ok!(KittiesMod::set_name(Origin::signed(ACCOUNT_ID_1), 0, b"Tommy".to_vec()));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 10_000 + 50);
+	});
+}
+
+#[test]
+fn set_name_refunds_previous_depositor()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::set_name(Origin::signed(ACCOUNT_ID_1), 0, b"Tom".to_vec()));
+		assert_ok!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 30);
+
+		assert_ok!(KittiesMod::set_name(Origin::signed(ACCOUNT_ID_2), 0, b"Jerry".to_vec()));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 0);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 10_000 + 50);
+	});
+}
+
+#[test]
+fn set_name_failed_not_owner()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::set_name(Origin::signed(ACCOUNT_ID_2), 0, b"Tom".to_vec()), Error::<Test>::NotOwner);
+		assert_noop!(KittiesMod::set_name(Origin::signed(ACCOUNT_ID_1), 1, b"Tom".to_vec()), Error::<Test>::InvalidKittyId);
+	});
+}
+
+#[test]
+fn set_name_failed_bad_metadata()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::set_name(Origin::signed(ACCOUNT_ID_1), 0, b"Tom Kitten".to_vec()), Error::<Test>::BadMetadata);
+	});
+}
+
+#[test]
+fn clear_name_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::set_name(Origin::signed(ACCOUNT_ID_1), 0, b"Tom".to_vec()));
+		assert_ok!(KittiesMod::clear_name(Origin::signed(ACCOUNT_ID_1), 0));
+		assert_eq!(KittiesMod::kitty_name(0), None);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 10_000);
+		assert_has_event!(Event::<Test>::KittyNameCleared(ACCOUNT_ID_1, 0));
+
+		assert_noop!(KittiesMod::clear_name(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::MetadataNotFound);
+	});
+}
+
+#[test]
+fn set_attribute_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::set_attribute(Origin::signed(ACCOUNT_ID_1), 0, b"color".to_vec(), b"red".to_vec()));
+		assert_eq!(KittiesMod::attribute_count(0), 1);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 10_000 + 80);
+		assert_has_event!(Event::<Test>::AttributeSet(ACCOUNT_ID_1, 0, b"color".to_vec().try_into().unwrap(), b"red".to_vec().try_into().unwrap()));
+
+		/* Overwrite keeps count */
+		assert_ok!(KittiesMod::set_attribute(Origin::signed(ACCOUNT_ID_1), 0, b"color".to_vec(), b"blue".to_vec()));
+		assert_eq!(KittiesMod::attribute_count(0), 1);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 10_000 + 90);
+		assert_eq!(<KittiesMod as nonfungible::Inspect<u64>>::attribute(&0, b"color"), Some(b"blue".to_vec()));
+	});
+}
+
+#[test]
+fn set_attribute_failed_too_many_attributes()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::set_attribute(Origin::signed(ACCOUNT_ID_1), 0, b"color".to_vec(), b"red".to_vec()));
+		assert_ok!(KittiesMod::set_attribute(Origin::signed(ACCOUNT_ID_1), 0, b"eyes".to_vec(), b"green".to_vec()));
+		assert_noop!(KittiesMod::set_attribute(Origin::signed(ACCOUNT_ID_1), 0, b"tail".to_vec(), b"long".to_vec()), Error::<Test>::TooManyAttributes);
+		assert_ok!(KittiesMod::set_attribute(Origin::signed(ACCOUNT_ID_1), 0, b"eyes".to_vec(), b"blue".to_vec()));
+	});
+}
+
+#[test]
+fn set_attribute_failed_not_enough_balance_reserved()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_4)));
+		assert_ok!(KittiesMod::set_attribute(Origin::signed(ACCOUNT_ID_4), 0, b"color".to_vec(), b"red".to_vec()));
+		assert_ok!(Balances::transfer(Origin::signed(ACCOUNT_ID_4), ACCOUNT_ID_1, 9_900));
+		assert_noop!(KittiesMod::set_attribute(Origin::signed(ACCOUNT_ID_4), 0, b"eyes".to_vec(), b"green".to_vec()), Error::<Test>::NotEnoughBalanceReserved);
+	});
+}
+
+#[test]
+fn clear_attribute_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::set_attribute(Origin::signed(ACCOUNT_ID_1), 0, b"color".to_vec(), b"red".to_vec()));
+		assert_ok!(KittiesMod::clear_attribute(Origin::signed(ACCOUNT_ID_1), 0, b"color".to_vec()));
+		assert_eq!(KittiesMod::attribute_count(0), 0);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 10_000);
+		assert_has_event!(Event::<Test>::AttributeCleared(ACCOUNT_ID_1, 0, b"color".to_vec().try_into().unwrap()));
+
+		assert_noop!(KittiesMod::clear_attribute(Origin::signed(ACCOUNT_ID_1), 0, b"color".to_vec()), Error::<Test>::MetadataNotFound);
+	});
+}
+
+#[test]
+fn burn_refunds_metadata()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::set_name(Origin::signed(ACCOUNT_ID_1), 0, b"Tom".to_vec()));
+		assert_ok!(KittiesMod::set_attribute(Origin::signed(ACCOUNT_ID_1), 0, b"color".to_vec(), b"red".to_vec()));
+		assert_ok!(KittiesMod::burn(Origin::signed(ACCOUNT_ID_1), 0));
+
+		assert_eq!(KittiesMod::kitty_name(0), None);
+		assert_eq!(KittyAttributes::<Test>::iter_prefix(0).count(), 0);
+		assert_eq!(KittiesMod::attribute_count(0), 0);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 0);
+	});
+}
+
+#[test]
+fn burn_refunds_open_offers()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_2), 0, 5_000, 10));
+		assert_ok!(KittiesMod::make_offer(Origin::signed(ACCOUNT_ID_4), 0, 2_000, 10));
+		assert_ok!(KittiesMod::burn(Origin::signed(ACCOUNT_ID_1), 0));
+
+		assert_eq!(Offers::<Test>::iter_prefix(0).count(), 0);
+		assert!(OfferExpiries::<Test>::get(10).is_empty());
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 0);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_4), 0);
+		assert_has_event!(Event::<Test>::OfferCancelled(ACCOUNT_ID_2, 0, 5_000));
+		assert_has_event!(Event::<Test>::OfferCancelled(ACCOUNT_ID_4, 0, 2_000));
+	});
+}
+
+/****************************************LOAN***************************************************/
+#[test]
+fn request_loan_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 2_000));
+		assert_ok!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_1), 0, 5_000, 6_000, 10));
+		assert_eq!(Loans::<Test>::get(0), Some(Loan { borrower: ACCOUNT_ID_1, lender: None, amount: 5_000, repayment: 6_000, duration: 10, deadline: None }));
+		assert_eq!(SaleList::<Test>::get(0), None);
+		assert_has_event!(Event::<Test>::KittyUnlisted(ACCOUNT_ID_1, 0));
+		assert_has_event!(Event::<Test>::LoanRequested(ACCOUNT_ID_1, 0, 5_000, 6_000, 10));
+
+		/* Pledged kitty is locked */
+		assert_noop!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 2_000), Error::<Test>::KittyLocked);
+		assert_noop!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2), Error::<Test>::KittyLocked);
+		assert_noop!(KittiesMod::burn(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::KittyLocked);
+		assert_noop!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_1), 0, 5_000, 6_000, 10), Error::<Test>::KittyLocked);
+	});
+}
+
+#[test]
+fn request_loan_failed_not_owner()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_2), 0, 5_000, 6_000, 10), Error::<Test>::NotOwner);
+		assert_noop!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_1), 1, 5_000, 6_000, 10), Error::<Test>::InvalidKittyId);
+	});
+}
+
+#[test]
+fn cancel_loan_request_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_1), 0, 5_000, 6_000, 10));
+		assert_ok!(KittiesMod::cancel_loan_request(Origin::signed(ACCOUNT_ID_1), 0));
+		assert_eq!(Loans::<Test>::get(0), None);
+		assert_has_event!(Event::<Test>::LoanCancelled(ACCOUNT_ID_1, 0));
+
+		assert_ok!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2));
+	});
+}
+
+#[test]
+fn cancel_loan_request_failed_already_funded()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_1), 0, 5_000, 6_000, 10));
+		assert_ok!(KittiesMod::fund_loan(Origin::signed(ACCOUNT_ID_2), 0));
+		assert_noop!(KittiesMod::cancel_loan_request(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::LoanAlreadyFunded);
+	});
+}
+
+#[test]
+fn fund_loan_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_4)));
+		assert_ok!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_4), 0, 5_000, 6_000, 10));
+		assert_ok!(KittiesMod::fund_loan(Origin::signed(ACCOUNT_ID_2), 0));
+
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_4), 10_000 + 5_000);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 100_000_000_000 - 5_000 - 10_000);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 10_000);
+		assert_eq!(LentCount::<Test>::get(ACCOUNT_ID_2), 1);
+		assert_eq!(Loans::<Test>::get(0).unwrap().deadline, Some(11));
+		assert_has_event!(Event::<Test>::LoanFunded(ACCOUNT_ID_2, ACCOUNT_ID_4, 0, 11));
+
+		assert_noop!(KittiesMod::fund_loan(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::LoanAlreadyFunded);
+	});
+}
+
+#[test]
+fn fund_loan_failed_own_loan()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_1), 0, 5_000, 6_000, 10));
+		assert_noop!(KittiesMod::fund_loan(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::AlreadyOwned);
+		assert_noop!(KittiesMod::fund_loan(Origin::signed(ACCOUNT_ID_2), 1), Error::<Test>::LoanNotFound);
+	});
+}
+
+#[test]
+fn repay_loan_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_4)));
+		assert_ok!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_4), 0, 5_000, 6_000, 10));
+		assert_ok!(KittiesMod::fund_loan(Origin::signed(ACCOUNT_ID_2), 0));
+
+		run_to_block(11);
+		assert_ok!(KittiesMod::repay_loan(Origin::signed(ACCOUNT_ID_4), 0));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_4), 10_000 - 1_000);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 100_000_000_000 + 1_000);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 0);
+		assert_eq!(LentCount::<Test>::get(ACCOUNT_ID_2), 0);
+		assert_eq!(Loans::<Test>::get(0), None);
+		assert_has_event!(Event::<Test>::LoanRepaid(ACCOUNT_ID_4, ACCOUNT_ID_2, 0, 6_000));
+
+		/* Kitty unlocked */
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_4), 0, 2_000));
+	});
+}
+
+#[test]
+fn repay_loan_failed_expired()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_1), 0, 5_000, 6_000, 10));
+		assert_noop!(KittiesMod::repay_loan(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::LoanNotFunded);
+		assert_ok!(KittiesMod::fund_loan(Origin::signed(ACCOUNT_ID_2), 0));
+
+		run_to_block(12);
+		assert_noop!(KittiesMod::repay_loan(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::LoanExpired);
+	});
+}
+
+#[test]
+fn claim_collateral_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_1), 0, 5_000, 6_000, 10));
+		assert_ok!(KittiesMod::fund_loan(Origin::signed(ACCOUNT_ID_2), 0));
+
+		run_to_block(11);
+		assert_noop!(KittiesMod::claim_collateral(Origin::signed(ACCOUNT_ID_2), 0), Error::<Test>::LoanNotExpired);
+
+		run_to_block(12);
+		assert_noop!(KittiesMod::claim_collateral(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::NotOwner);
+		assert_ok!(KittiesMod::claim_collateral(Origin::signed(ACCOUNT_ID_2), 0));
+		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_2));
+		assert_eq!(Loans::<Test>::get(0), None);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 0);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 10_000);
+		assert_has_event!(Event::<Test>::CollateralClaimed(ACCOUNT_ID_2, ACCOUNT_ID_1, 0));
+	});
+}
+
+#[test]
+fn claim_collateral_works_with_lender_at_max_owned()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_1), 0, 5_000, 6_000, 10));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::fund_loan(Origin::signed(ACCOUNT_ID_2), 0));
+
+		/* Collateral takes the last slot of lender */
+		assert_noop!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)), Error::<Test>::ExceedMaxOwned);
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_1), 3, ACCOUNT_ID_2), Error::<Test>::ExceedMaxOwned);
+
+		run_to_block(12);
+		assert_ok!(KittiesMod::claim_collateral(Origin::signed(ACCOUNT_ID_2), 0));
+		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_2).unwrap().len(), 3);
+		assert_eq!(LentCount::<Test>::get(ACCOUNT_ID_2), 0);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 3 * 10_000);
+	});
+}
+
+#[test]
+fn fund_loan_failed_lender_at_max_owned()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::request_loan(Origin::signed(ACCOUNT_ID_1), 0, 5_000, 6_000, 10));
+		for _ in 0..3
+		{
+			assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		}
+
+		assert_noop!(KittiesMod::fund_loan(Origin::signed(ACCOUNT_ID_2), 0), Error::<Test>::ExceedMaxOwned);
+	});
+}
+
+/****************************************SWAP***************************************************/
+#[test]
+fn propose_swap_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::propose_swap(Origin::signed(ACCOUNT_ID_1), 0, 1, Some(TopUp::FromProposer(1_000))));
+		assert_eq!(Swaps::<Test>::get(0), Some(Swap { proposer: ACCOUNT_ID_1, their_kitty: 1, top_up: Some(TopUp::FromProposer(1_000)) }));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 10_000 + 1_000);
+		assert_has_event!(Event::<Test>::SwapProposed(ACCOUNT_ID_1, 0, 1, Some(TopUp::FromProposer(1_000))));
+
+		assert_noop!(KittiesMod::propose_swap(Origin::signed(ACCOUNT_ID_1), 0, 1, None), Error::<Test>::SwapAlreadyExists);
+	});
+}
+
+#[test]
+fn propose_swap_failed()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+
+		assert_noop!(KittiesMod::propose_swap(Origin::signed(ACCOUNT_ID_1), 0, 3, None), Error::<Test>::InvalidKittyId);
+		assert_noop!(KittiesMod::propose_swap(Origin::signed(ACCOUNT_ID_1), 2, 0, None), Error::<Test>::NotOwner);
+		assert_noop!(KittiesMod::propose_swap(Origin::signed(ACCOUNT_ID_1), 0, 1, None), Error::<Test>::AlreadyOwned);
+	});
+}
+
+#[test]
+fn cancel_swap_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::propose_swap(Origin::signed(ACCOUNT_ID_1), 0, 1, Some(TopUp::FromProposer(1_000))));
+
+		assert_noop!(KittiesMod::cancel_swap(Origin::signed(ACCOUNT_ID_2), 0), Error::<Test>::NotOwner);
+		assert_ok!(KittiesMod::cancel_swap(Origin::signed(ACCOUNT_ID_1), 0));
+		assert_eq!(Swaps::<Test>::get(0), None);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 10_000);
+		assert_has_event!(Event::<Test>::SwapCancelled(ACCOUNT_ID_1, 0));
+
+		assert_noop!(KittiesMod::cancel_swap(Origin::signed(ACCOUNT_ID_1), 0), Error::<Test>::SwapNotFound);
+	});
+}
+
+#[test]
+fn accept_swap_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_2), 1, 2_000));
+		assert_ok!(KittiesMod::propose_swap(Origin::signed(ACCOUNT_ID_1), 0, 1, Some(TopUp::FromProposer(1_000))));
+		assert_ok!(KittiesMod::accept_swap(Origin::signed(ACCOUNT_ID_2), 0));
+
+		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_2));
+		assert_eq!(KittyOwnedBy::<Test>::get(1), Some(ACCOUNT_ID_1));
+		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_1), Some(bounded_vec![1]));
+		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_2), Some(bounded_vec![0]));
+		assert_eq!(SaleList::<Test>::get(1), None);
+		assert_eq!(Swaps::<Test>::get(0), None);
+
+		/* Top up paid from proposer reserve */
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 10_000);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 100_000_000_000 - 10_000 - 1_000);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 100_000_000_000 - 10_000 + 1_000);
+		assert_has_event!(Event::<Test>::SwapAccepted(ACCOUNT_ID_1, ACCOUNT_ID_2, 0, 1, Some(TopUp::FromProposer(1_000))));
+	});
+}
+
+#[test]
+fn accept_swap_works_with_counterparty_top_up_at_max_owned()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::propose_swap(Origin::signed(ACCOUNT_ID_1), 0, 3, Some(TopUp::FromCounterparty(1_000))));
+		assert_ok!(KittiesMod::accept_swap(Origin::signed(ACCOUNT_ID_2), 0));
+
+		assert_eq!(KittyOwnedBy::<Test>::get(3), Some(ACCOUNT_ID_1));
+		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_1).unwrap().len(), 3);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 100_000_000_000 - 30_000 + 1_000);
+	});
+}
+
+#[test]
+fn accept_swap_failed()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_noop!(KittiesMod::accept_swap(Origin::signed(ACCOUNT_ID_2), 0), Error::<Test>::SwapNotFound);
+
+		assert_ok!(KittiesMod::propose_swap(Origin::signed(ACCOUNT_ID_1), 0, 1, None));
+		assert_noop!(KittiesMod::accept_swap(Origin::signed(ACCOUNT_ID_4), 0), Error::<Test>::NotOwner);
+	});
+}
+
+#[test]
+fn swap_cleared_on_transfer()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::propose_swap(Origin::signed(ACCOUNT_ID_1), 0, 1, Some(TopUp::FromProposer(1_000))));
+		assert_ok!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_4));
+
+		assert_eq!(Swaps::<Test>::get(0), None);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 0);
+		assert_noop!(KittiesMod::accept_swap(Origin::signed(ACCOUNT_ID_2), 0), Error::<Test>::SwapNotFound);
+	});
+}
+
+/****************************************NONFUNGIBLE***************************************************/
+#[test]
+fn nonfungible_inspect_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+
+		assert_eq!(<KittiesMod as nonfungible::Inspect<u64>>::owner(&0), Some(ACCOUNT_ID_1));
+		assert_eq!(<KittiesMod as nonfungible::Inspect<u64>>::owner(&1), None);
+		assert_eq!(<KittiesMod as nonfungible::Inspect<u64>>::attribute(&0, b"dna"), Some(Kitties::<Test>::get(0).unwrap().0.to_vec()));
+		assert!(<KittiesMod as nonfungible::Inspect<u64>>::can_transfer(&0));
+	});
+}
+
+#[test]
+fn nonfungible_transfer_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(<KittiesMod as nonfungible::Transfer<u64>>::transfer(&0, &ACCOUNT_ID_2));
+		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_2));
+		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_2), Some(bounded_vec![0]));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 10_000);
+
+		assert_has_event!(Event::<Test>::KittyTransferred(ACCOUNT_ID_1, ACCOUNT_ID_2, 0));
+	});
+}
+
+#[test]
+fn nonfungible_transfer_failed_pending_kitty()
+{
+	new_test_ext().execute_with(|| {
+		RevealDelay::set(&3);
+
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert!(!<KittiesMod as nonfungible::Inspect<u64>>::can_transfer(&0));
+		assert_noop!(<KittiesMod as nonfungible::Transfer<u64>>::transfer(&0, &ACCOUNT_ID_2), Error::<Test>::InvalidKittyId);
+	});
+}
+
+#[test]
+fn nonfungible_mint_into_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(<KittiesMod as nonfungible::Mutate<u64>>::mint_into(&0, &ACCOUNT_ID_1));
+		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_1));
+		assert!(Kitties::<Test>::get(0).is_some());
+
+		assert_noop!(<KittiesMod as nonfungible::Mutate<u64>>::mint_into(&5, &ACCOUNT_ID_1), Error::<Test>::InvalidKittyId);
+	});
+}
+
+#[test]
+fn nonfungible_burn_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(<KittiesMod as nonfungible::Mutate<u64>>::burn(&0, Some(&ACCOUNT_ID_2)), Error::<Test>::NotOwner);
+		assert_ok!(<KittiesMod as nonfungible::Mutate<u64>>::burn(&0, Some(&ACCOUNT_ID_1)));
+		assert_eq!(KittyOwnedBy::<Test>::get(0), None);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 0);
+	});
+}
+
+/****************************************RUNTIME API***************************************************/
+#[test]
+fn kitties_of_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_eq!(KittiesMod::kitties_of(ACCOUNT_ID_1, 0, 10), Page { items: vec![], next: None });
+
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_eq!(KittiesMod::kitties_of(ACCOUNT_ID_1, 0, 10), Page { items: vec![0, 2], next: None });
+		assert_eq!(KittiesMod::kitties_of(ACCOUNT_ID_1, 0, 1), Page { items: vec![0], next: Some(1) });
+		assert_eq!(KittiesMod::kitties_of(ACCOUNT_ID_1, 1, 1), Page { items: vec![2], next: None });
+		assert_eq!(KittiesMod::kitties_of(ACCOUNT_ID_1, 5, 1), Page { items: vec![], next: None });
+	});
+}
+
+#[test]
+fn kitty_details_works()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::transfer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_2), 0, 2_000));
+		assert_ok!(KittiesMod::set_name(Origin::signed(ACCOUNT_ID_2), 0, b"Tom".to_vec()));
+
+		assert_eq!(KittiesMod::kitty_details(0), Some(KittyDetails {
+			dna: Kitties::<Test>::get(0),
+			owner: ACCOUNT_ID_2,
+			creator: Some(ACCOUNT_ID_1),
+			name: Some(b"Tom".to_vec()),
+			price: Some(2_000),
+		}));
+		assert_eq!(KittiesMod::kitty_details(1), None);
+	});
+}
+
+#[test]
+fn all_kitties_paginated()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::burn(Origin::signed(ACCOUNT_ID_1), 1));
+
+		let ids = |page: Page<(u32, KittyDetails<u64, u128>), u32>| (page.items.into_iter().map(|(id, _)| id).collect::<Vec<_>>(), page.next);
+		/* Burned id counts toward the limit */
+		assert_eq!(ids(KittiesMod::all_kitties(0, 2)), (vec![0], Some(2)));
+		assert_eq!(ids(KittiesMod::all_kitties(2, 2)), (vec![2, 3], None));
+		assert_eq!(ids(KittiesMod::all_kitties(4, 2)), (vec![], None));
+	});
+}
+
+#[test]
+fn on_sale_paginated()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_1), 0, 1_000));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_2), 2, 3_000));
+		assert_ok!(KittiesMod::sell(Origin::signed(ACCOUNT_ID_2), 3, 4_000));
+
+		assert_eq!(KittiesMod::on_sale(0, 3), Page { items: vec![(0, 1_000), (2, 3_000)], next: Some(3) });
+		assert_eq!(KittiesMod::on_sale(3, 3), Page { items: vec![(3, 4_000)], next: None });
+	});
+}
+
+#[test]
+fn page_checks_at_most_max_page_limit_ids()
+{
+	new_test_ext().execute_with(|| {
+		KittyCount::<Test>::put(MAX_PAGE_LIMIT + 10);
+
+		/* No kitty stored, the page still stops after the limit */
+		assert_eq!(KittiesMod::all_kitties(0, u32::MAX), Page { items: vec![], next: Some(MAX_PAGE_LIMIT) });
+		assert_eq!(KittiesMod::on_sale(MAX_PAGE_LIMIT, u32::MAX), Page { items: vec![], next: None });
+	});
+}
+
+/****************************************GENESIS***************************************************/
+#[test]
+fn genesis_config_works()
+{
+	new_test_ext_with_kitties(vec![(ACCOUNT_ID_1, [1u8; 16]), (ACCOUNT_ID_2, [2u8; 16]), (ACCOUNT_ID_1, [3u8; 16])]).execute_with(|| {
+		assert_eq!(KittyCount::<Test>::get(), 3);
+		assert_eq!(Kitties::<Test>::get(0), Some(Kitty([1u8; 16])));
+		assert_eq!(Kitties::<Test>::get(2), Some(Kitty([3u8; 16])));
+		assert_eq!(KittyOwnedBy::<Test>::get(1), Some(ACCOUNT_ID_2));
+		assert_eq!(OwnedKitty::<Test>::get(ACCOUNT_ID_1), Some(bounded_vec![0, 2]));
+		assert_eq!(KittyInfos::<Test>::get(1), Some(KittyInfo { creator: ACCOUNT_ID_2 }));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 20_000);
+
+		/* New kitty follows founders */
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_eq!(KittyOwnedBy::<Test>::get(3), Some(ACCOUNT_ID_2));
+	});
+}
+
+#[test]
+#[should_panic(expected = "Founder kitty owner must be endowed")]
+fn genesis_config_failed_not_enough_balance_reserved()
+{
+	new_test_ext_with_kitties(vec![(ACCOUNT_ID_3, [1u8; 16])]);
+}
+
+/****************************************INSTANCES***************************************************/
+#[test]
+fn instances_have_separate_collections()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(Dogs::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(Dogs::create(Origin::signed(ACCOUNT_ID_2)));
+
+		/* Each collection counts its own ids */
+		assert_eq!(KittyCount::<Test>::get(), 1);
+		assert_eq!(KittyCount::<Test, Instance1>::get(), 2);
+		assert_eq!(KittyOwnedBy::<Test, Instance1>::get(1), Some(ACCOUNT_ID_2));
+		assert_eq!(KittyOwnedBy::<Test>::get(1), None);
+
+		/* Dogs reserve less */
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 10_000 + 5_000);
+
+		System::assert_has_event(TestEvent::Dogs(Event::<Test, Instance1>::KittyCreated(ACCOUNT_ID_2, 1, Kitties::<Test, Instance1>::get(1).unwrap())));
+	});
+}
+
+#[test]
+fn instances_have_separate_limits()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(Dogs::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_err!(Dogs::create(Origin::signed(ACCOUNT_ID_1)), Error::<Test, Instance1>::ExceedMaxOwned);
+
+		/* Kitties limit is unaffected */
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+	});
+}
+
+#[test]
+fn instances_transfer_separately()
+{
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesMod::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(Dogs::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(Dogs::transfer(Origin::signed(ACCOUNT_ID_1), 0, ACCOUNT_ID_2));
+
+		assert_eq!(KittyOwnedBy::<Test, Instance1>::get(0), Some(ACCOUNT_ID_2));
+		assert_eq!(KittyOwnedBy::<Test>::get(0), Some(ACCOUNT_ID_1));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 10_000);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 5_000);
+	});
+}
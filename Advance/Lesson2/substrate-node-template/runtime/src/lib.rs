@@ -14,7 +14,10 @@ use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, Verify},
+	traits::{
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount,
+		NumberFor, Verify,
+	},
 	transaction_validity::{TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature,
 };
@@ -27,13 +30,14 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
+		ConstU128, ConstU32, ConstU64, ConstU8, Currency, KeyOwnerProofSystem, OnUnbalanced,
+		Randomness, StorageInfo,
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
@@ -59,6 +63,9 @@ pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::Account
 /// Balance of an account.
 pub type Balance = u128;
 
+/// Index of a kitty.
+pub type KittyIndex = u32;
+
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
@@ -146,6 +153,39 @@ parameter_types! {
 	pub const Revered: u32 = 10_000;
 
 	pub const MaxOwnedAllowed: u32 = 5;
+
+	pub const KittiesMinimumPrice: Balance = 1_000;
+
+	pub const KittiesStringLimit: u32 = 32;
+
+	pub const KittiesMaxAttributes: u32 = 8;
+
+	pub const KittiesDepositPerByte: Balance = 10;
+
+	pub const MaxExpiringOffers: u32 = 64;
+
+	pub const KittiesMaxOfferDuration: BlockNumber = 7 * DAYS;
+
+	/// Kitty dna is revealed a few blocks after create/breed. Collective flip randomness can still
+	/// be influenced by block authors, the delay only makes grinding for a dna harder.
+	pub const KittiesRevealDelay: BlockNumber = 3;
+
+	pub const MaxPendingReveals: u32 = 64;
+
+	pub const KittiesMarketplaceFee: Permill = Permill::from_percent(2);
+
+	pub const KittiesCreatorRoyalty: Permill = Permill::from_percent(5);
+
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	pub TreasuryAccount: AccountId = TreasuryPalletId::get().into_account_truncating();
+}
+
+/// Deposits kitties marketplace fees into the treasury account.
+pub struct ToTreasury;
+impl OnUnbalanced<pallet_balances::NegativeImbalance<Runtime>> for ToTreasury {
+	fn on_nonzero_unbalanced(amount: pallet_balances::NegativeImbalance<Runtime>) {
+		Balances::resolve_creating(&TreasuryAccount::get(), amount);
+	}
 }
 
 // Configure FRAME pallets to include in runtime.
@@ -278,10 +318,21 @@ impl pallet_poe::Config for Runtime {
 impl pallet_kitties::Config for Runtime {
 	type Event = Event;
 	type Randomness = RandomnessCollectiveFlip;
-	type KittyIndex = u32;
+	type RevealDelay = KittiesRevealDelay;
+	type MaxPendingReveals = MaxPendingReveals;
+	type KittyIndex = KittyIndex;
 	type Currency = Balances;
 	type Reserved = Revered;
 	type MaxOwnedAllowed = MaxOwnedAllowed;
+	type MinimumPrice = KittiesMinimumPrice;
+	type StringLimit = KittiesStringLimit;
+	type MaxAttributes = KittiesMaxAttributes;
+	type DepositPerByte = KittiesDepositPerByte;
+	type MaxExpiringOffers = MaxExpiringOffers;
+	type MaxOfferDuration = KittiesMaxOfferDuration;
+	type MarketplaceFee = KittiesMarketplaceFee;
+	type CreatorRoyalty = KittiesCreatorRoyalty;
+	type OnMarketplaceFee = ToTreasury;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -482,6 +533,27 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_runtime_api::KittiesApi<Block, AccountId, KittyIndex, Balance> for Runtime {
+		fn kitties_of(owner: AccountId, start: u32, limit: u32) -> pallet_kitties::Page<KittyIndex, u32> {
+			Kitties::kitties_of(owner, start, limit)
+		}
+
+		fn all_kitties(
+			start: KittyIndex,
+			limit: u32,
+		) -> pallet_kitties::Page<(KittyIndex, pallet_kitties::KittyDetails<AccountId, Balance>), KittyIndex> {
+			Kitties::all_kitties(start, limit)
+		}
+
+		fn on_sale(start: KittyIndex, limit: u32) -> pallet_kitties::Page<(KittyIndex, Balance), KittyIndex> {
+			Kitties::on_sale(start, limit)
+		}
+
+		fn kitty_details(kitty_id: KittyIndex) -> Option<pallet_kitties::KittyDetails<AccountId, Balance>> {
+			Kitties::kitty_details(kitty_id)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -0,0 +1,165 @@
+//! RPC to inspect and clear data `pallet-template` indexed off chain.
+//!
+//! Items live in the node's persistent offchain storage under `node-template::indexing::`,
+//! keyed by block number and sequence number, see `pallet_template::indexing`. Offchain storage
+//! can't be iterated by prefix, so methods take a range of blocks and read the item count kept
+//! for each block.
+//!
+//! Register it in the node's `rpc.rs`:
+//!
+//! ```ignore
+//! if let Some(storage) = backend.offchain_storage() {
+//! 	module.merge(Indexing::new(storage, deny_unsafe).into_rpc())?;
+//! }
+//! ```
+//!
+//! Reads are always allowed, `templateIndexing_clear` needs `--rpc-methods unsafe` on public
+//! interfaces.
+
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use pallet_template::{indexing::IndexedItem, IndexingData, INDEXING_QUEUE};
+use parking_lot::RwLock;
+use sc_rpc_api::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_core::{offchain::OffchainStorage, Bytes};
+use sp_offchain::STORAGE_PREFIX;
+use sp_runtime::traits::AtLeast32BitUnsigned;
+
+#[cfg(test)]
+mod tests;
+
+/// Most blocks a single call reads or clears.
+pub const MAX_BLOCK_RANGE: u32 = 1_000;
+
+/// Error code of a block range above [`MAX_BLOCK_RANGE`] or ending before it starts.
+const BAD_RANGE_ERROR: i32 = 1;
+
+/// Item indexed by `extrinsic` in a block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedEntry<BlockNumber> {
+	pub block_number: BlockNumber,
+	pub seq: u32,
+	/// Offchain storage key of the item.
+	pub key: Bytes,
+	/// `None` when the stored value doesn't decode as `IndexingData`.
+	pub data: Option<IndexedData>,
+}
+
+/// Decoded `IndexingData`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedData {
+	pub label: String,
+	pub number: u32,
+}
+
+#[rpc(client, server)]
+pub trait IndexingApi<BlockNumber> {
+	/// Items still queued for blocks `from..=to`.
+	#[method(name = "templateIndexing_items")]
+	fn items(&self, from: BlockNumber, to: BlockNumber) -> RpcResult<Vec<IndexedEntry<BlockNumber>>>;
+
+	/// Remove items queued for blocks `from..=to`, returning how many were removed. Unsafe.
+	#[method(name = "templateIndexing_clear")]
+	fn clear(&self, from: BlockNumber, to: BlockNumber) -> RpcResult<u32>;
+}
+
+/// Implements [`IndexingApiServer`] over the node's offchain storage.
+pub struct Indexing<S: OffchainStorage> {
+	storage: Arc<RwLock<S>>,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<S: OffchainStorage> Indexing<S> {
+	pub fn new(storage: S, deny_unsafe: DenyUnsafe) -> Self {
+		Self { storage: Arc::new(RwLock::new(storage)), deny_unsafe }
+	}
+
+	/* Blocks of a checked range, with the item count of each */
+	fn blocks<BlockNumber>(&self, from: BlockNumber, to: BlockNumber) -> RpcResult<Vec<(BlockNumber, u32)>>
+	where
+		BlockNumber: AtLeast32BitUnsigned + Copy + Encode,
+	{
+		if from > to || to - from >= MAX_BLOCK_RANGE.into() {
+			return Err(CallError::Custom(ErrorObject::owned(
+				BAD_RANGE_ERROR,
+				format!("Block range must be ordered and span at most {} blocks", MAX_BLOCK_RANGE),
+				None::<()>,
+			))
+			.into())
+		}
+
+		let storage = self.storage.read();
+		let mut blocks = Vec::new();
+		let mut block = from;
+		loop {
+			let len = storage
+				.get(STORAGE_PREFIX, &INDEXING_QUEUE.len_key(&block))
+				.and_then(|len| u32::decode(&mut &len[..]).ok())
+				.unwrap_or_default();
+			blocks.push((block, len));
+			if block == to {
+				break
+			}
+			block += BlockNumber::one();
+		}
+		Ok(blocks)
+	}
+}
+
+impl<S, BlockNumber> IndexingApiServer<BlockNumber> for Indexing<S>
+where
+	S: OffchainStorage + 'static,
+	BlockNumber: AtLeast32BitUnsigned + Copy + Encode + Send + Sync + Serialize + for<'de> Deserialize<'de> + 'static,
+{
+	fn items(&self, from: BlockNumber, to: BlockNumber) -> RpcResult<Vec<IndexedEntry<BlockNumber>>> {
+		let blocks = self.blocks(from, to)?;
+		let storage = self.storage.read();
+
+		let mut entries = Vec::new();
+		for (block_number, len) in blocks {
+			for seq in 0..len {
+				let key = INDEXING_QUEUE.item_key(&block_number, seq);
+				/* Acknowledged by the worker */
+				let value = match storage.get(STORAGE_PREFIX, &key) {
+					Some(value) => value,
+					None => continue,
+				};
+
+				let data = IndexedItem::<IndexingData>::decode(&mut &value[..]).ok().map(|item| IndexedData {
+					label: String::from_utf8_lossy(&item.data.0).into_owned(),
+					number: item.data.1,
+				});
+				entries.push(IndexedEntry { block_number, seq, key: key.into(), data });
+			}
+		}
+		Ok(entries)
+	}
+
+	fn clear(&self, from: BlockNumber, to: BlockNumber) -> RpcResult<u32> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let blocks = self.blocks(from, to)?;
+		let mut storage = self.storage.write();
+
+		let mut removed = 0;
+		for (block_number, len) in blocks {
+			for seq in 0..len {
+				let key = INDEXING_QUEUE.item_key(&block_number, seq);
+				if storage.get(STORAGE_PREFIX, &key).is_some() {
+					storage.remove(STORAGE_PREFIX, &key);
+					removed += 1;
+				}
+			}
+			storage.remove(STORAGE_PREFIX, &INDEXING_QUEUE.len_key(&block_number));
+		}
+		Ok(removed)
+	}
+}
@@ -0,0 +1,64 @@
+use super::*;
+use sp_core::offchain::storage::InMemOffchainStorage;
+
+// Offchain storage with items written as `extrinsic` indexes them, plus one undecodable item.
+fn indexed_storage() -> InMemOffchainStorage {
+	let mut storage = InMemOffchainStorage::default();
+	for (block, seq, number) in [(1u32, 0u32, 7u32), (1, 1, 8), (3, 0, 9)] {
+		let item = IndexedItem { seq, data: IndexingData(b"my_offchain_storage".to_vec(), number) };
+		storage.set(STORAGE_PREFIX, &INDEXING_QUEUE.item_key(&block, seq), &item.encode());
+		storage.set(STORAGE_PREFIX, &INDEXING_QUEUE.len_key(&block), &(seq + 1).encode());
+	}
+	storage.set(STORAGE_PREFIX, &INDEXING_QUEUE.item_key(&3u32, 1), &[1]);
+	storage.set(STORAGE_PREFIX, &INDEXING_QUEUE.len_key(&3u32), &2u32.encode());
+	storage
+}
+
+fn entry(block_number: u32, seq: u32, number: Option<u32>) -> IndexedEntry<u32> {
+	IndexedEntry {
+		block_number,
+		seq,
+		key: INDEXING_QUEUE.item_key(&block_number, seq).into(),
+		data: number.map(|number| IndexedData { label: "my_offchain_storage".into(), number }),
+	}
+}
+
+#[test]
+fn items_lists_indexed_blocks_in_range() {
+	let indexing = Indexing::new(indexed_storage(), DenyUnsafe::Yes);
+
+	assert_eq!(
+		IndexingApiServer::<u32>::items(&indexing, 0, 3).unwrap(),
+		vec![entry(1, 0, Some(7)), entry(1, 1, Some(8)), entry(3, 0, Some(9)), entry(3, 1, None)]
+	);
+	assert_eq!(IndexingApiServer::<u32>::items(&indexing, 2, 2).unwrap(), vec![]);
+}
+
+#[test]
+fn items_rejects_bad_range() {
+	let indexing = Indexing::new(indexed_storage(), DenyUnsafe::No);
+
+	assert!(IndexingApiServer::<u32>::items(&indexing, 3, 1).is_err());
+	assert!(IndexingApiServer::<u32>::items(&indexing, 0, MAX_BLOCK_RANGE).is_err());
+	assert!(IndexingApiServer::<u32>::items(&indexing, 1, MAX_BLOCK_RANGE).is_ok());
+}
+
+#[test]
+fn clear_removes_items() {
+	let indexing = Indexing::new(indexed_storage(), DenyUnsafe::No);
+
+	assert_eq!(IndexingApiServer::<u32>::clear(&indexing, 1, 2).unwrap(), 2);
+	assert_eq!(
+		IndexingApiServer::<u32>::items(&indexing, 0, 3).unwrap(),
+		vec![entry(3, 0, Some(9)), entry(3, 1, None)]
+	);
+	assert!(indexing.storage.read().get(STORAGE_PREFIX, &INDEXING_QUEUE.len_key(&1u32)).is_none());
+}
+
+#[test]
+fn clear_is_unsafe() {
+	let indexing = Indexing::new(indexed_storage(), DenyUnsafe::Yes);
+
+	assert!(IndexingApiServer::<u32>::clear(&indexing, 1, 3).is_err());
+	assert_eq!(IndexingApiServer::<u32>::items(&indexing, 0, 3).unwrap().len(), 4);
+}
@@ -4,16 +4,136 @@ use super::*;
 
 #[allow(unused)]
 use crate::Pallet as Template;
+use codec::{Decode, Encode};
 use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::{
+	traits::{EnsureOrigin, Get},
+	BoundedVec,
+};
 use frame_system::RawOrigin;
+use sp_runtime::traits::{IdentifyAccount, One, TrailingZeroInput, Zero};
+use sp_std::{vec, vec::Vec};
+
+// Distinct keys without a keystore, only ever stored, never verified.
+fn public<T: Config>(seed: u32) -> T::Public {
+	T::Public::decode(&mut TrailingZeroInput::new(&(0u8, seed).encode())).expect("infinite input; qed")
+}
+
+// Signature of unsigned calls, checked in `validate_unsigned` and not when dispatched.
+fn signature<T: Config>() -> T::Signature {
+	T::Signature::decode(&mut TrailingZeroInput::zeroes()).expect("infinite input; qed")
+}
+
+// Fill the authority set up to `count` keys.
+fn set_authorities<T: Config>(count: u32) {
+	let authorities: Vec<_> = (0..count).map(public::<T>).collect();
+	Authorities::<T>::put(BoundedVec::try_from(authorities).expect("at most MaxAuthorities; qed"));
+}
 
 benchmarks! {
-	do_something {
-		let s in 0 .. 100;
+	increment {
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller.clone()), T::MaxValue::get())
+	verify {
+		assert_eq!(Counters::<T>::get(&caller), T::MaxValue::get());
+	}
+
+	decrement {
+		let caller: T::AccountId = whitelisted_caller();
+		Counters::<T>::insert(&caller, T::MaxValue::get());
+	}: _(RawOrigin::Signed(caller.clone()), T::MaxValue::get())
+	verify {
+		assert_eq!(Counters::<T>::get(&caller), 0);
+	}
+
+	reset {
+		let who: T::AccountId = whitelisted_caller();
+		Counters::<T>::insert(&who, T::MaxValue::get());
+		let origin = T::AdminOrigin::successful_origin();
+	}: _<T::Origin>(origin, who.clone())
+	verify {
+		assert!(!Counters::<T>::contains_key(&who));
+	}
+
+	// Only writes the item and length of the queue with offchain indexing, charged as two
+	// writes in `weights.rs`.
+	extrinsic {
 		let caller: T::AccountId = whitelisted_caller();
-	}: _(RawOrigin::Signed(caller), s)
+	}: _(RawOrigin::Signed(caller), u32::MAX)
+
+	// Worst case finds the caller last in a full authority set.
+	submit_aggregate {
+		set_authorities::<T>(T::MaxAuthorities::get());
+		let caller = public::<T>(T::MaxAuthorities::get().saturating_sub(1)).into_account();
+		frame_system::Pallet::<T>::set_block_number(2u32.into());
+	}: _(RawOrigin::Signed(caller), u32::MAX, One::one())
+	verify {
+		assert_eq!(Aggregate::<T>::get(), u32::MAX);
+		assert_eq!(LastAggregatedBlock::<T>::get(), One::one());
+	}
+
+	submit_data_unsigned_with_signed_payload {
+		frame_system::Pallet::<T>::set_block_number(2u32.into());
+		let payload = DataPayload {
+			block_number: frame_system::Pallet::<T>::block_number(),
+			to: One::one(),
+			value: u32::MAX,
+			public: public::<T>(0),
+		};
+	}: _(RawOrigin::None, payload, signature::<T>())
+	verify {
+		assert_eq!(Aggregate::<T>::get(), u32::MAX);
+	}
+
+	// Worst case overwrites a slot of a full ring.
+	submit_window_stats_unsigned {
+		let window = WindowStats {
+			from: Zero::zero(),
+			to: Zero::zero(),
+			stats: NumberStats { count: u32::MAX, sum: u64::MAX, min: 0, max: u32::MAX },
+		};
+		for n in 0..T::MaxWindowStats::get() {
+			WindowStatsRing::<T>::insert(n, window.clone());
+		}
+		WindowStatsCount::<T>::put(T::MaxWindowStats::get());
+		let payload = StatsPayload {
+			block_number: frame_system::Pallet::<T>::block_number(),
+			window,
+			public: public::<T>(0),
+		};
+	}: _(RawOrigin::None, payload, signature::<T>())
+	verify {
+		assert_eq!(WindowStatsCount::<T>::get(), T::MaxWindowStats::get() + 1);
+	}
+
+	// Worst case finds the caller last in a full authority set and drops the oldest price of a
+	// full buffer.
+	submit_price {
+		set_authorities::<T>(T::MaxAuthorities::get());
+		let caller = public::<T>(T::MaxAuthorities::get().saturating_sub(1)).into_account();
+		let prices = vec![0; T::MaxPrices::get() as usize];
+		Prices::<T>::put(BoundedVec::try_from(prices).expect("MaxPrices prices; qed"));
+	}: _(RawOrigin::Signed(caller), u32::MAX)
+	verify {
+		assert_eq!(Prices::<T>::get().last(), Some(&u32::MAX));
+	}
+
+	// Worst case scans a set one key short of full.
+	add_authority {
+		set_authorities::<T>(T::MaxAuthorities::get().saturating_sub(1));
+		let authority = public::<T>(T::MaxAuthorities::get());
+	}: _(RawOrigin::Root, authority.clone())
+	verify {
+		assert!(Authorities::<T>::get().contains(&authority));
+	}
+
+	// Worst case removes the last key of a full set.
+	remove_authority {
+		set_authorities::<T>(T::MaxAuthorities::get());
+		let authority = public::<T>(T::MaxAuthorities::get().saturating_sub(1));
+	}: _(RawOrigin::Root, authority.clone())
 	verify {
-		assert_eq!(Something::<T>::get(), Some(s));
+		assert!(!Authorities::<T>::get().contains(&authority));
 	}
 
 	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
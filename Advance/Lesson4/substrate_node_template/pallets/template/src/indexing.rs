@@ -0,0 +1,110 @@
+//! Off-chain indexing queue.
+//!
+//! On chain, [`Queue::push`] writes each item with offchain indexing under its own key, made of
+//! the queue prefix, the block number and a per-block sequence number such as the extrinsic
+//! index, so several extrinsics in a block no longer overwrite each other. Off chain,
+//! [`Queue::items`] reads the items of a block and [`Queue::clear`] removes them once consumed.
+//!
+//! ```ignore
+//! const QUEUE: Queue = Queue::new(b"my-pallet::queue::");
+//!
+//! // In a call.
+//! let seq = frame_system::Pallet::<T>::extrinsic_index().unwrap_or_default();
+//! QUEUE.push(&block_number, seq, data);
+//!
+//! // In the off-chain worker.
+//! submit(QUEUE.items::<_, Data>(&block_number));
+//! QUEUE.clear(&block_number);
+//! ```
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	offchain::{storage::StorageValueRef, StorageKind},
+	RuntimeDebug,
+};
+use sp_std::vec::Vec;
+
+/* Suffix of the key holding the number of items pushed in a block */
+const LEN_SUFFIX: &[u8] = b"::len";
+
+/// Item stored in the queue, `seq` is its position among the items of its block.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct IndexedItem<T> {
+	pub seq: u32,
+	pub data: T,
+}
+
+/// Queue of items under a key prefix, unique per queue.
+pub struct Queue {
+	prefix: &'static [u8],
+}
+
+impl Queue {
+	pub const fn new(prefix: &'static [u8]) -> Self {
+		Self { prefix }
+	}
+
+	/// Key of item `seq` of `block`.
+	pub fn item_key<B: Encode>(&self, block: &B, seq: u32) -> Vec<u8> {
+		let mut key = self.block_key(block);
+		seq.encode_to(&mut key);
+		key
+	}
+
+	/// Key of the number of items pushed in `block`.
+	pub fn len_key<B: Encode>(&self, block: &B) -> Vec<u8> {
+		let mut key = self.block_key(block);
+		key.extend_from_slice(LEN_SUFFIX);
+		key
+	}
+
+	/// Index `data` as item `seq` of `block`. Must be called on chain, with a `seq` unique in the
+	/// block and increasing with each push.
+	pub fn push<B: Encode, T: Encode>(&self, block: &B, seq: u32, data: T) {
+		let item = IndexedItem { seq, data };
+		sp_io::offchain_index::set(&self.item_key(block, seq), &item.encode());
+		sp_io::offchain_index::set(&self.len_key(block), &seq.saturating_add(1).encode());
+	}
+
+	/// Items of `block` still in the queue, in order, leaving them in place. Items that fail to
+	/// decode are skipped. Must be called off chain.
+	pub fn items<B: Encode, T: Decode>(&self, block: &B) -> Vec<IndexedItem<T>> {
+		let len_key = self.len_key(block);
+		let len = StorageValueRef::persistent(&len_key).get::<u32>().ok().flatten().unwrap_or_default();
+
+		(0..len)
+			.filter_map(|seq| {
+				let key = self.item_key(block, seq);
+				StorageValueRef::persistent(&key).get::<IndexedItem<T>>().ok().flatten()
+			})
+			.collect()
+	}
+
+	/// Remove all items of `block`, returning how many were left. Must be called off chain.
+	pub fn clear<B: Encode>(&self, block: &B) -> u32 {
+		let len_key = self.len_key(block);
+		let mut len_ref = StorageValueRef::persistent(&len_key);
+		let len = match len_ref.get::<u32>() {
+			Ok(Some(len)) => len,
+			_ => return 0,
+		};
+
+		let mut removed = 0;
+		for seq in 0..len {
+			let key = self.item_key(block, seq);
+			if sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, &key).is_some() {
+				removed += 1;
+			}
+			StorageValueRef::persistent(&key).clear();
+		}
+		len_ref.clear();
+		removed
+	}
+
+	fn block_key<B: Encode>(&self, block: &B) -> Vec<u8> {
+		let mut key = self.prefix.to_vec();
+		block.encode_to(&mut key);
+		key
+	}
+}
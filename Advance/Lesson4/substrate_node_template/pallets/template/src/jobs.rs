@@ -0,0 +1,120 @@
+//! Off-chain job scheduler.
+//!
+//! Runs a registry of [`Job`]s from one `offchain_worker` hook, each every `period` blocks. The
+//! block of a job's last successful run is kept in persistent local storage, so failed jobs retry
+//! on the next block. Jobs share a time budget per run, jobs left when it runs out wait for the
+//! next block. Each outcome is logged under the `runtime::template::jobs` target as
+//! `job=<name> block=<number> outcome=<outcome> elapsed_ms=<ms>`.
+
+use codec::{Decode, Encode};
+use sp_runtime::{
+	offchain::{storage::StorageValueRef, Duration},
+	traits::AtLeast32BitUnsigned,
+	RuntimeDebug,
+};
+use sp_std::{fmt::Debug, vec::Vec};
+
+const LOG_TARGET: &str = "runtime::template::jobs";
+
+/// Job run every `period` blocks.
+pub struct Job<BlockNumber> {
+	/// Unique in a scheduler, part of the last-run key.
+	pub name: &'static str,
+	pub period: u32,
+	pub run: fn(BlockNumber) -> Result<(), &'static str>,
+}
+
+/// Outcome of a job in a run.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Outcome {
+	/// Ran without error, last run marker updated.
+	Done,
+	/// Ran and failed, retried on the next block.
+	Failed(&'static str),
+	/// Period not elapsed since the last run.
+	NotDue,
+	/// Time budget of the run used up by earlier jobs.
+	OverBudget,
+}
+
+/// Runs jobs with last run markers under a local storage key prefix, unique per scheduler.
+pub struct Scheduler {
+	prefix: &'static [u8],
+	budget_ms: u64,
+}
+
+impl Scheduler {
+	pub const fn new(prefix: &'static [u8], budget_ms: u64) -> Self {
+		Self { prefix, budget_ms }
+	}
+
+	/// Key of the block `name` last ran successfully at.
+	pub fn last_run_key(&self, name: &str) -> Vec<u8> {
+		let mut key = self.prefix.to_vec();
+		key.extend_from_slice(name.as_bytes());
+		key
+	}
+
+	/// Run jobs due at `block_number` in order while the budget lasts, returning each outcome.
+	/// Must be called off chain.
+	pub fn run<BlockNumber>(
+		&self,
+		jobs: &[Job<BlockNumber>],
+		block_number: BlockNumber,
+	) -> Vec<(&'static str, Outcome)>
+	where
+		BlockNumber: AtLeast32BitUnsigned + Copy + Encode + Decode + Debug,
+	{
+		let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(self.budget_ms));
+
+		jobs.iter()
+			.map(|job| {
+				let start = sp_io::offchain::timestamp();
+				let outcome = self.run_job(job, block_number, start > deadline);
+				let elapsed_ms = sp_io::offchain::timestamp().diff(&start).millis();
+
+				let level = match outcome {
+					Outcome::Failed(_) => log::Level::Warn,
+					_ => log::Level::Info,
+				};
+				log::log!(
+					target: LOG_TARGET,
+					level,
+					"job={} block={:?} outcome={:?} elapsed_ms={}",
+					job.name,
+					block_number,
+					outcome,
+					elapsed_ms
+				);
+				(job.name, outcome)
+			})
+			.collect()
+	}
+
+	fn run_job<BlockNumber>(&self, job: &Job<BlockNumber>, block_number: BlockNumber, over_budget: bool) -> Outcome
+	where
+		BlockNumber: AtLeast32BitUnsigned + Copy + Encode + Decode,
+	{
+		let key = self.last_run_key(job.name);
+		let last_run = StorageValueRef::persistent(&key);
+
+		/* Due on first run, then once the period elapsed */
+		if let Ok(Some(last)) = last_run.get::<BlockNumber>() {
+			if block_number < last.saturating_add(job.period.into()) {
+				return Outcome::NotDue
+			}
+		}
+
+		if over_budget {
+			return Outcome::OverBudget
+		}
+
+		match (job.run)(block_number) {
+			Ok(()) => {
+				last_run.set(&block_number);
+				Outcome::Done
+			},
+			Err(e) => Outcome::Failed(e),
+		}
+	}
+}
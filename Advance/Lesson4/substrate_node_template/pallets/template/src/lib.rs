@@ -4,6 +4,8 @@
 /// Learn more about FRAME and the core library of Substrate FRAME pallets:
 /// <https://docs.substrate.io/reference/frame-pallets/>
 pub use pallet::*;
+pub use weights::WeightInfo;
+
 #[cfg(test)]
 mod mock;
 
@@ -13,18 +15,205 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod indexing;
+pub mod jobs;
+pub mod weights;
+
+use sp_core::crypto::KeyTypeId;
+
+/// Key type of off-chain worker signing keys, inserted into the node keystore with
+/// `author_insertKey`.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"tmpl");
+
+/// Crypto used by the off-chain worker to sign transactions, sr25519 under [`KEY_TYPE`].
+pub mod crypto {
+	use super::KEY_TYPE;
+	#[cfg(test)]
+	use sp_core::sr25519::Signature as Sr25519Signature;
+	#[cfg(test)]
+	use sp_runtime::traits::Verify;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+	app_crypto!(sr25519, KEY_TYPE);
+
+	pub struct TestAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for TestAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+
+	// Used in tests, where accounts are plain sr25519 keys.
+	#[cfg(test)]
+	impl frame_system::offchain::AppCrypto<<Sr25519Signature as Verify>::Signer, Sr25519Signature>
+		for TestAuthId
+	{
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
+	use super::WeightInfo;
 	use frame_support::pallet_prelude::*;
-	use frame_system::pallet_prelude::*;
+	use frame_system::{
+		offchain::{
+			AppCrypto, CreateSignedTransaction, SendSignedTransaction, SendUnsignedTransaction,
+			SignedPayload, Signer, SigningTypes,
+		},
+		pallet_prelude::*,
+	};
 	use serde::{Deserialize, Deserializer};
-	use sp_io::offchain_index;
-	use sp_runtime::offchain::storage::StorageValueRef;
+	use sp_runtime::{
+		offchain::{
+			http,
+			storage::StorageValueRef,
+			storage_lock::{BlockAndTime, StorageLock},
+			Duration, StorageKind,
+		},
+		traits::{CheckedSub, IdentifyAccount, One, Saturating, Zero},
+		transaction_validity::{
+			InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
+			ValidTransaction,
+		},
+		SaturatedConversion,
+	};
 	use sp_std::vec::Vec;
 
-	/* Indexing data struct */
+	use crate::{
+		indexing::Queue,
+		jobs::{Job, Scheduler},
+	};
+
+	/// Label and number indexed by `extrinsic`.
 	#[derive(Debug, Deserialize, Encode, Decode, Default)]
-	struct IndexingData(#[serde(deserialize_with = "de_string_to_bytes")] Vec<u8>, u32);
+	pub struct IndexingData(#[serde(deserialize_with = "de_string_to_bytes")] pub Vec<u8>, pub u32);
+
+	/// Value submitted by an off-chain worker without fees, signed by an authority key.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct DataPayload<Public, BlockNumber> {
+		pub block_number: BlockNumber,
+		/// Last indexed block the value covers, from the block after `LastAggregatedBlock`.
+		pub to: BlockNumber,
+		pub value: u32,
+		pub public: Public,
+	}
+
+	impl<T: SigningTypes> SignedPayload<T> for DataPayload<T::Public, T::BlockNumber> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
+
+	/// Count, sum, minimum and maximum of a set of indexed numbers.
+	#[derive(Encode, Decode, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct NumberStats {
+		pub count: u32,
+		pub sum: u64,
+		pub min: u32,
+		pub max: u32,
+	}
+
+	impl NumberStats {
+		pub fn add(&mut self, number: u32) {
+			self.merge(&NumberStats { count: 1, sum: number.into(), min: number, max: number });
+		}
+
+		pub fn merge(&mut self, other: &NumberStats) {
+			if other.count == 0 {
+				return
+			}
+			if self.count == 0 {
+				*self = *other;
+				return
+			}
+			self.count = self.count.saturating_add(other.count);
+			self.sum = self.sum.saturating_add(other.sum);
+			self.min = self.min.min(other.min);
+			self.max = self.max.max(other.max);
+		}
+
+		/// Mean rounded down, `None` without numbers.
+		pub fn mean(&self) -> Option<u64> {
+			self.sum.checked_div(self.count.into())
+		}
+	}
+
+	/// Statistics of numbers indexed in blocks `from..=to`.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct WindowStats<BlockNumber> {
+		pub from: BlockNumber,
+		pub to: BlockNumber,
+		pub stats: NumberStats,
+	}
+
+	/// Window statistics computed by an off-chain worker, signed by an authority key.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct StatsPayload<Public, BlockNumber> {
+		pub block_number: BlockNumber,
+		pub window: WindowStats<BlockNumber>,
+		pub public: Public,
+	}
+
+	impl<T: SigningTypes> SignedPayload<T> for StatsPayload<T::Public, T::BlockNumber> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
+
+	/// Local storage key of the price endpoint, set by the node operator with
+	/// `offchain_localStorageSet`. Price fetching is off while unset.
+	pub const PRICE_URL_KEY: &[u8] = b"node-template::price-url";
+
+	/// Queue of data indexed by `extrinsic`.
+	pub const INDEXING_QUEUE: Queue = Queue::new(b"node-template::indexing::");
+
+	/// Local storage key of the lock held while processing indexed blocks.
+	pub(crate) const LOCK_KEY: &[u8] = b"node-template::indexing-lock";
+
+	/// Local storage key of the `NextUnsignedAt` value and block of the last unsigned aggregate
+	/// this node sent.
+	pub(crate) const UNSIGNED_SENT_KEY: &[u8] = b"node-template::unsigned-sent";
+
+	/// Local storage key of the last indexed block processed by this node.
+	pub(crate) const LAST_PROCESSED_KEY: &[u8] = b"node-template::last-processed";
+
+	/* Lock expires after this many blocks or milliseconds, whichever comes last */
+	const LOCK_BLOCK_EXPIRATION: u32 = 3;
+	const LOCK_TIMEOUT_EXPIRATION: u64 = 4_000;
+
+	/* Prefix of per-block statistics kept until they leave the window */
+	const BLOCK_STATS_PREFIX: &[u8] = b"node-template::block-stats::";
+
+	/* Scheduler of off-chain jobs, all due jobs of a run share the budget */
+	pub(crate) const JOB_SCHEDULER: Scheduler = Scheduler::new(b"node-template::job-last-run::", 5_000);
+
+	/* Blocks between runs of each job */
+	const AGGREGATE_PERIOD: u32 = 1;
+	const FETCH_PRICE_PERIOD: u32 = 5;
+	const CLEANUP_PERIOD: u32 = 10;
+
+	/// Local storage key of the last indexed block cleaned up.
+	pub(crate) const CLEANED_UP_TO_KEY: &[u8] = b"node-template::cleaned-up-to";
+
+	/* Processed blocks keep unaggregated items this long, a cleanup run clears at most this many */
+	pub(crate) const INDEXING_RETENTION: u32 = 100;
+	const MAX_CLEANUP: u32 = 32;
+
+	/* Indexed blocks a single run catches up on, older ones are skipped */
+	const MAX_CATCH_UP: u32 = 16;
+
+	/* Price endpoint response, e.g. `{"USD": 6.23}` */
+	#[derive(Deserialize)]
+	struct PriceResponse {
+		#[serde(rename = "USD")]
+		usd: f64,
+	}
 
 	/* Deserialize method */
 	pub fn de_string_to_bytes<'de, D>(de: D) -> Result<Vec<u8>, D::Error>
@@ -41,18 +230,97 @@ pub mod pallet {
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The identifier type for an offchain worker.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		/// Blocks to wait after an unsigned submission before accepting the next one.
+		#[pallet::constant]
+		type UnsignedInterval: Get<Self::BlockNumber>;
+
+		/// Priority of unsigned submissions in the transaction pool.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Maximum number of keys allowed to submit unsigned payloads.
+		#[pallet::constant]
+		type MaxAuthorities: Get<u32>;
+
+		/// Number of latest prices in the moving average.
+		#[pallet::constant]
+		type MaxPrices: Get<u32>;
+
+		/// Number of blocks in the window of indexed numbers the off-chain worker aggregates.
+		#[pallet::constant]
+		type WindowSize: Get<u32>;
+
+		/// Number of latest window statistics kept on chain.
+		#[pallet::constant]
+		type MaxWindowStats: Get<u32>;
+
+		/// Highest value a counter can reach.
+		#[pallet::constant]
+		type MaxValue: Get<u32>;
+
+		/// Origin allowed to reset counters.
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	// The pallet's runtime storage items.
 	// https://docs.substrate.io/main-docs/build/runtime-storage/
 	#[pallet::storage]
-	#[pallet::getter(fn something)]
+	#[pallet::getter(fn counter)]
 	// Learn more about declaring storage items:
 	// https://docs.substrate.io/main-docs/build/runtime-storage/#declaring-storage-items
-	pub type Something<T> = StorageValue<_, u32>;
+	pub type Counters<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// Sum of values submitted by off-chain workers.
+	#[pallet::storage]
+	#[pallet::getter(fn aggregate)]
+	pub type Aggregate<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// Latest prices in cents, oldest first.
+	#[pallet::storage]
+	#[pallet::getter(fn prices)]
+	pub type Prices<T: Config> = StorageValue<_, BoundedVec<u32, T::MaxPrices>, ValueQuery>;
+
+	/// Last indexed block covered by the aggregate. Submissions cover the blocks after it, so
+	/// each block is added once whichever authority submits it.
+	#[pallet::storage]
+	#[pallet::getter(fn last_aggregated_block)]
+	pub type LastAggregatedBlock<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// Keys whose signed payloads are accepted as unsigned transactions.
+	#[pallet::storage]
+	#[pallet::getter(fn authorities)]
+	#[pallet::unbounded]
+	pub type Authorities<T: Config> = StorageValue<_, BoundedVec<T::Public, T::MaxAuthorities>, ValueQuery>;
+
+	/// Earliest block an unsigned submission is accepted at.
+	#[pallet::storage]
+	#[pallet::getter(fn next_unsigned_at)]
+	pub type NextUnsignedAt<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// Ring buffer of window statistics, slot `n % MaxWindowStats` holds the `n`th submission.
+	#[pallet::storage]
+	#[pallet::getter(fn window_stats)]
+	pub type WindowStatsRing<T: Config> = StorageMap<_, Twox64Concat, u32, WindowStats<T::BlockNumber>>;
+
+	/// Number of window statistics submitted so far.
+	#[pallet::storage]
+	#[pallet::getter(fn window_stats_count)]
+	pub type WindowStatsCount<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// Earliest block window statistics are accepted at.
+	#[pallet::storage]
+	#[pallet::getter(fn next_stats_at)]
+	pub type NextStatsAt<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
 
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/main-docs/build/events-errors/
@@ -60,17 +328,46 @@ pub mod pallet {
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		/// Event documentation should end with an array that provides descriptive names for event
-		/// parameters. [something, who]
-		SomethingStored(u32, T::AccountId),
+		/// parameters. [who, value]
+		CounterIncremented(T::AccountId, u32),
+		/// Counter decreased. [who, value]
+		CounterDecremented(T::AccountId, u32),
+		/// Counter reset by the admin origin. [who]
+		CounterReset(T::AccountId),
+		/// Off-chain worker submitted the value of indexed blocks up to `to`, aggregate updated.
+		/// [value, to, aggregate, who]
+		AggregateSubmitted(u32, T::BlockNumber, u32, T::AccountId),
+		/// Key allowed to submit unsigned payloads. [authority]
+		AuthorityAdded(T::Public),
+		/// Key no longer allowed to submit unsigned payloads. [authority]
+		AuthorityRemoved(T::Public),
+		/// Off-chain worker submitted a price in cents. [price, who]
+		PriceSubmitted(u32, T::AccountId),
+		/// Off-chain worker submitted statistics of a window of blocks. [window]
+		WindowStatsSubmitted(WindowStats<T::BlockNumber>),
 	}
 
 	// Errors inform users that something went wrong.
 	#[pallet::error]
 	pub enum Error<T> {
-		/// Error names should be descriptive.
-		NoneValue,
 		/// Errors should have helpful documentation associated with them.
 		StorageOverflow,
+		/// Counter would go above `MaxValue`.
+		CounterAboveMax,
+		/// Counter would go below zero.
+		CounterBelowZero,
+		/// Account has no counter to reset.
+		CounterNotSet,
+		/// Key is already an authority.
+		AlreadyAuthority,
+		/// Key is not an authority.
+		NotAuthority,
+		/// Authority set is full.
+		TooManyAuthorities,
+		/// Submission covers blocks already in the aggregate.
+		AlreadyAggregated,
+		/// Submission covers blocks not finished yet.
+		BlockNotFinished,
 	}
 
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -78,59 +375,168 @@ pub mod pallet {
 	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// An example dispatchable that takes a singles value as a parameter, writes the value to
-		/// storage and emits an event. This function must be dispatched by a signed extrinsic.
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
-		pub fn do_something(origin: OriginFor<T>, something: u32) -> DispatchResult {
+		/// Add `by` to the caller's counter, up to `MaxValue`.
+		#[pallet::weight(T::WeightInfo::increment())]
+		pub fn increment(origin: OriginFor<T>, by: u32) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
-			// This function will return an error if the extrinsic is not signed.
 			// https://docs.substrate.io/main-docs/build/origins/
 			let who = ensure_signed(origin)?;
 
-			// Update storage.
-			<Something<T>>::put(something);
+			let value = Counters::<T>::try_mutate(&who, |value| -> Result<u32, DispatchError> {
+				*value = value
+					.checked_add(by)
+					.filter(|new| *new <= T::MaxValue::get())
+					.ok_or(Error::<T>::CounterAboveMax)?;
+				Ok(*value)
+			})?;
 
-			// Emit an event.
-			Self::deposit_event(Event::SomethingStored(something, who));
-			// Return a successful DispatchResultWithPostInfo
+			Self::deposit_event(Event::CounterIncremented(who, value));
 			Ok(())
 		}
 
-		/// An example dispatchable that may throw a custom error.
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1))]
-		pub fn cause_error(origin: OriginFor<T>) -> DispatchResult {
-			let _who = ensure_signed(origin)?;
+		/// Subtract `by` from the caller's counter, down to zero.
+		#[pallet::weight(T::WeightInfo::decrement())]
+		pub fn decrement(origin: OriginFor<T>, by: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-			// Read a value from storage.
-			match <Something<T>>::get() {
-				// Return an error if the value has not been set.
-				None => return Err(Error::<T>::NoneValue.into()),
-				Some(old) => {
-					// Increment the value read from storage; will error in the event of overflow.
-					let new = old.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
-					// Update the value in storage with the incremented result.
-					<Something<T>>::put(new);
-					Ok(())
-				},
-			}
+			let value = Counters::<T>::try_mutate(&who, |value| -> Result<u32, DispatchError> {
+				*value = value.checked_sub(by).ok_or(Error::<T>::CounterBelowZero)?;
+				Ok(*value)
+			})?;
+
+			Self::deposit_event(Event::CounterDecremented(who, value));
+			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
+		/// Remove the counter of `who`. Only callable by `AdminOrigin`.
+		#[pallet::weight(T::WeightInfo::reset())]
+		pub fn reset(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			ensure!(Counters::<T>::contains_key(&who), Error::<T>::CounterNotSet);
+			Counters::<T>::remove(&who);
+
+			Self::deposit_event(Event::CounterReset(who));
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::extrinsic())]
 		pub fn extrinsic(origin: OriginFor<T>, number: u32) -> DispatchResult {
 			/* Check signature */
 			let _who = ensure_signed(origin)?;
 
 			/* Get block number */
 			let block_number = frame_system::Pallet::<T>::block_number();
-			/* Generate store key */
-			let key = Self::derived_key(block_number.clone());
+			/* Position in this block's queue, unique per extrinsic */
+			let seq = frame_system::Pallet::<T>::extrinsic_index().unwrap_or_default();
 
 			/* Data to index */
 			let data = IndexingData(b"my_offchain_storage".to_vec(), number);
+			log::info!("extrinsic index data: {:?}, seq: {:?}, block number: {:?}", data, seq, block_number);
+
 			/* Store data with offchain indexing */
-			offchain_index::set(&key, &data.encode());
+			INDEXING_QUEUE.push(&block_number, seq, data);
+			Ok(())
+		}
+
+		/// Add the value of indexed blocks after `LastAggregatedBlock` up to `to`, computed by the
+		/// off-chain worker, to the on-chain aggregate. Only callable by authority accounts.
+		#[pallet::weight(T::WeightInfo::submit_aggregate())]
+		pub fn submit_aggregate(origin: OriginFor<T>, value: u32, to: T::BlockNumber) -> DispatchResult {
+			/* Check signature */
+			let who = ensure_signed(origin)?;
+			Self::ensure_authority(&who)?;
+
+			Self::add_to_aggregate(value, to, who)
+		}
+
+		/// Add a value signed by an authority key to the aggregate, without fees. Checked in
+		/// `validate_unsigned` before reaching the pool.
+		#[pallet::weight(T::WeightInfo::submit_data_unsigned_with_signed_payload())]
+		pub fn submit_data_unsigned_with_signed_payload(
+			origin: OriginFor<T>,
+			payload: DataPayload<T::Public, T::BlockNumber>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			/* Unsigned only, signature checked in validate_unsigned */
+			ensure_none(origin)?;
+
+			/* Wait interval before next unsigned submission */
+			let now = frame_system::Pallet::<T>::block_number();
+			<NextUnsignedAt<T>>::put(now.saturating_add(T::UnsignedInterval::get()));
+
+			Self::add_to_aggregate(payload.value, payload.to, payload.public.into_account())
+		}
+
+		/// Store window statistics signed by an authority key, overwriting the oldest when the
+		/// ring buffer is full. Checked in `validate_unsigned` before reaching the pool.
+		#[pallet::weight(T::WeightInfo::submit_window_stats_unsigned())]
+		pub fn submit_window_stats_unsigned(
+			origin: OriginFor<T>,
+			payload: StatsPayload<T::Public, T::BlockNumber>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			/* Unsigned only, signature checked in validate_unsigned */
+			ensure_none(origin)?;
+
+			/* Wait interval before next statistics */
+			let now = frame_system::Pallet::<T>::block_number();
+			<NextStatsAt<T>>::put(now.saturating_add(T::UnsignedInterval::get()));
+
+			/* Write into the next slot of the ring */
+			let count = Self::window_stats_count();
+			<WindowStatsRing<T>>::insert(count % T::MaxWindowStats::get().max(1), payload.window.clone());
+			<WindowStatsCount<T>>::put(count.wrapping_add(1));
+
+			Self::deposit_event(Event::WindowStatsSubmitted(payload.window));
+			Ok(())
+		}
+
+		/// Add a price fetched by the off-chain worker to the moving average, dropping the
+		/// oldest price when full. Only callable by authority accounts.
+		#[pallet::weight(T::WeightInfo::submit_price())]
+		pub fn submit_price(origin: OriginFor<T>, price: u32) -> DispatchResult {
+			/* Check signature */
+			let who = ensure_signed(origin)?;
+			Self::ensure_authority(&who)?;
+
+			<Prices<T>>::mutate(|prices| {
+				if prices.try_push(price).is_err() {
+					prices.remove(0);
+					let _ = prices.try_push(price);
+				}
+			});
+
+			Self::deposit_event(Event::PriceSubmitted(price, who));
+			Ok(())
+		}
+
+		/// Allow a key to submit aggregates, prices and unsigned payloads.
+		#[pallet::weight(T::WeightInfo::add_authority())]
+		pub fn add_authority(origin: OriginFor<T>, authority: T::Public) -> DispatchResult {
+			ensure_root(origin)?;
+
+			<Authorities<T>>::try_mutate(|authorities| {
+				ensure!(!authorities.contains(&authority), Error::<T>::AlreadyAuthority);
+				authorities.try_push(authority.clone()).map_err(|_| Error::<T>::TooManyAuthorities)
+			})?;
 
-			log::info!("extrinsic set local storage key: {:?}, data: {:?}, block number: {:?}", key, &data.encode(), block_number);
+			Self::deposit_event(Event::AuthorityAdded(authority));
+			Ok(())
+		}
+
+		/// Stop a key from submitting unsigned payloads.
+		#[pallet::weight(T::WeightInfo::remove_authority())]
+		pub fn remove_authority(origin: OriginFor<T>, authority: T::Public) -> DispatchResult {
+			ensure_root(origin)?;
+
+			<Authorities<T>>::try_mutate(|authorities| {
+				let index = authorities.iter().position(|a| *a == authority).ok_or(Error::<T>::NotAuthority)?;
+				authorities.remove(index);
+				Ok::<(), Error<T>>(())
+			})?;
+
+			Self::deposit_event(Event::AuthorityRemoved(authority));
 			Ok(())
 		}
 	}
@@ -140,35 +546,428 @@ pub mod pallet {
 	{
 		fn offchain_worker(block_number: T::BlockNumber)
 		{
-			/* Generate store key from block number */
-			let key = Self::derived_key(block_number - 1u32.into());
+			/* Run due jobs, outcomes are logged by the scheduler */
+			JOB_SCHEDULER.run(&Self::jobs(), block_number);
+		}
+	}
 
-			/* Create persistent storage reference */
-			let storage_ref = StorageValueRef::persistent(&key);
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
 
-			/* Get indexing data */
-			if let Ok(Some(data)) = storage_ref.get::<IndexingData>()
-			{
-				log::info!("Get local storage data: {:?}, block number {:?} ", data.1, block_number);
-			}
-			else 
-			{
-				log::info!("Error reading from local storage. Block number: {:?}", block_number);
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::submit_data_unsigned_with_signed_payload { ref payload, ref signature } => {
+					/* Payload must be signed by the key it names */
+					let signature_valid =
+						SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone());
+					if !signature_valid {
+						return InvalidTransaction::BadProof.into()
+					}
+
+					/* Blocks already added by another authority */
+					if payload.to <= Self::last_aggregated_block() {
+						return InvalidTransaction::Stale.into()
+					}
+
+					Self::validate_transaction_parameters(
+						"TemplateOffchainWorker",
+						Self::next_unsigned_at(),
+						&payload.block_number,
+						&payload.public,
+					)
+				},
+				Call::submit_window_stats_unsigned { ref payload, ref signature } => {
+					let signature_valid =
+						SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone());
+					if !signature_valid {
+						return InvalidTransaction::BadProof.into()
+					}
+
+					/* Window must end before the block it is submitted at */
+					let window = &payload.window;
+					if window.from > window.to || window.to >= payload.block_number {
+						return InvalidTransaction::Call.into()
+					}
+
+					Self::validate_transaction_parameters(
+						"TemplateWindowStats",
+						Self::next_stats_at(),
+						&payload.block_number,
+						&payload.public,
+					)
+				},
+				_ => InvalidTransaction::Call.into(),
 			}
 		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// Off-chain jobs, run in this order.
+		pub(crate) fn jobs() -> [Job<T::BlockNumber>; 3] {
+			[
+				Job { name: "aggregate", period: AGGREGATE_PERIOD, run: Self::process_indexed },
+				Job { name: "fetch", period: FETCH_PRICE_PERIOD, run: Self::fetch_and_submit_price },
+				Job { name: "cleanup", period: CLEANUP_PERIOD, run: Self::cleanup_indexed },
+			]
+		}
+
+		/// Process indexed data of blocks before `block_number`, each block once per node. Runs on
+		/// other forks or concurrent workers skip blocks already processed.
+		fn process_indexed(block_number: T::BlockNumber) -> Result<(), &'static str> {
+			/* Nothing indexed before genesis */
+			if block_number.is_zero() {
+				return Ok(())
+			}
+			let target = block_number - One::one();
+
+			/* Aggregates and statistics are submitted by authorities only */
+			if !Self::local_authority_can_sign() {
+				log::debug!("No authority key in the keystore, indexed blocks not processed");
+				return Ok(())
+			}
+
+			/* One worker at a time, released on drop or expiry if the worker dies */
+			let mut lock = StorageLock::<BlockAndTime<frame_system::Pallet<T>>>::with_block_and_time_deadline(
+				LOCK_KEY,
+				LOCK_BLOCK_EXPIRATION,
+				Duration::from_millis(LOCK_TIMEOUT_EXPIRATION),
+			);
+			let _guard = lock.try_lock().map_err(|_| "Indexed blocks locked by another worker")?;
+
+			/* Continue after last processed block, first run only processes the latest */
+			let last_processed = StorageValueRef::persistent(LAST_PROCESSED_KEY);
+			let mut next = match last_processed.get::<T::BlockNumber>() {
+				Ok(Some(last)) if last >= target => return Ok(()),
+				Ok(Some(last)) =>
+					(last + One::one()).max(target.saturating_sub((MAX_CATCH_UP - 1).into())),
+				_ => target,
+			};
+
+			while next <= target {
+				Self::record_block_stats(next);
+				next += One::one();
+			}
+
+			last_processed.set(&target);
+
+			/* Items stay queued until aggregated on chain */
+			let result = Self::submit_queued_items(target, block_number);
+
+			if let Err(e) = Self::submit_window_stats(target, block_number) {
+				log::info!("Window statistics not submitted: {}", e);
+			}
+			result
+		}
+
+		/// Fetch price and submit it when an endpoint is configured.
+		fn fetch_and_submit_price(_block_number: T::BlockNumber) -> Result<(), &'static str> {
+			/* Prices are submitted by authorities only, skip the request */
+			if !Self::local_authority_can_sign() {
+				log::debug!("No authority key in the keystore, price not fetched");
+				return Ok(())
+			}
+			match sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, PRICE_URL_KEY) {
+				Some(url) => Self::fetch_price(&url)
+					.map_err(|_| "Failed to fetch price")
+					.and_then(|price| Self::send_signed(Call::submit_price { price })),
+				None => Ok(()),
+			}
+		}
+
+		/// Remove items of processed blocks older than `INDEXING_RETENTION` the worker failed to
+		/// submit, oldest first.
+		pub(crate) fn cleanup_indexed(_block_number: T::BlockNumber) -> Result<(), &'static str> {
+			let last_processed = match StorageValueRef::persistent(LAST_PROCESSED_KEY).get::<T::BlockNumber>() {
+				Ok(Some(last)) => last,
+				_ => return Ok(()),
+			};
+			let until = match last_processed.checked_sub(&INDEXING_RETENTION.into()) {
+				Some(until) => until,
+				None => return Ok(()),
+			};
+
+			/* Continue after last cleaned block, bounded per run */
+			let cleaned_up_to = StorageValueRef::persistent(CLEANED_UP_TO_KEY);
+			let oldest = until.saturating_sub((MAX_CLEANUP - 1).into());
+			let mut next = match cleaned_up_to.get::<T::BlockNumber>() {
+				Ok(Some(cleaned)) if cleaned >= until => return Ok(()),
+				Ok(Some(cleaned)) => (cleaned + One::one()).max(oldest),
+				_ => oldest,
+			};
+
+			while next <= until {
+				let removed = INDEXING_QUEUE.clear(&next);
+				if removed > 0 {
+					log::info!("Removed unaggregated indexed items: {:?}, indexed block number: {:?}", removed, next);
+				}
+				next += One::one();
+			}
+
+			cleaned_up_to.set(&until);
+			Ok(())
+		}
+
+		/// Keep statistics of numbers indexed in `indexed_block` while in the window, drop the
+		/// block sliding out.
+		fn record_block_stats(indexed_block: T::BlockNumber) {
+			let mut stats = NumberStats::default();
+			for item in INDEXING_QUEUE.items::<_, IndexingData>(&indexed_block) {
+				log::info!("Get indexed data: {:?}, seq: {:?}, indexed block number {:?}", item.data.1, item.seq, indexed_block);
+				stats.add(item.data.1);
+			}
+
+			if stats.count > 0 {
+				StorageValueRef::persistent(&Self::block_stats_key(indexed_block)).set(&stats);
+			}
+			if let Some(expired) = indexed_block.checked_sub(&T::WindowSize::get().into()) {
+				StorageValueRef::persistent(&Self::block_stats_key(expired)).clear();
+			}
+		}
+
+		/// Submit the sum of items queued for blocks after `LastAggregatedBlock` up to `to`, at most
+		/// the last `MAX_CATCH_UP`, in one unsigned transaction per interval. Items stay queued
+		/// until the chain covers their block, so a payload the pool drops is sent again in a later
+		/// interval.
+		fn submit_queued_items(to: T::BlockNumber, block_number: T::BlockNumber) -> Result<(), &'static str> {
+			let aggregated = Self::last_aggregated_block();
+			let mut queued = false;
+			let mut total: u32 = 0;
+			let mut block = to.saturating_sub((MAX_CATCH_UP - 1).into());
+			while block <= to {
+				if block <= aggregated {
+					/* Applied on chain, by this node or another authority */
+					INDEXING_QUEUE.clear(&block);
+				} else {
+					for item in INDEXING_QUEUE.items::<_, IndexingData>(&block) {
+						total = total.checked_add(item.data.1).ok_or("Queued items overflow the aggregate")?;
+						queued = true;
+					}
+				}
+				block += One::one();
+			}
+
+			if !queued {
+				return Ok(())
+			}
+
+			if !Self::unsigned_aggregate_due(block_number) {
+				log::debug!("Queued items wait for the next unsigned interval");
+				return Ok(())
+			}
+			Self::submit_aggregate_unsigned(block_number, to, total)
+		}
+
+		/// Submit statistics of numbers indexed in the window of blocks ending at `to`.
+		fn submit_window_stats(to: T::BlockNumber, block_number: T::BlockNumber) -> Result<(), &'static str> {
+			/* Pool would reject it as stale, skip the work */
+			if Self::next_stats_at() > block_number {
+				return Err("Too early to send window statistics")
+			}
+
+			let from = to.saturating_sub(T::WindowSize::get().saturating_sub(1).into());
+			let mut stats = NumberStats::default();
+			let mut block = from;
+			while block <= to {
+				if let Ok(Some(block_stats)) =
+					StorageValueRef::persistent(&Self::block_stats_key(block)).get::<NumberStats>()
+				{
+					stats.merge(&block_stats);
+				}
+				block += One::one();
+			}
+
+			if stats.count == 0 {
+				return Err("No numbers indexed in window")
+			}
+
+			let window = WindowStats { from, to, stats };
+			let result = Signer::<T, T::AuthorityId>::any_account()
+				.with_filter(Self::authorities().into_inner())
+				.send_unsigned_transaction(
+					|account| StatsPayload { block_number, window: window.clone(), public: account.public.clone() },
+					|payload, signature| Call::submit_window_stats_unsigned { payload, signature },
+				);
+
+			match result {
+				Some((_, Ok(()))) => Ok(()),
+				Some((_, Err(()))) => Err("Failed to submit unsigned transaction"),
+				None => Err("No authority key signed the payload"),
+			}
+		}
+
+		fn block_stats_key(block_number: T::BlockNumber) -> Vec<u8> {
+			let mut key = BLOCK_STATS_PREFIX.to_vec();
+			block_number.encode_to(&mut key);
+			key
+		}
 
-		#[deny(clippy::clone_double_ref)]
-		fn derived_key(block_number: T::BlockNumber) -> Vec<u8> {
-			block_number.using_encoded(|encoded_bn| {
-				b"node-template::indexing::"
-					.iter()
-					.chain(encoded_bn)
-					.copied()
-					.collect::<Vec<u8>>()
+		/// Latest window statistics, oldest first.
+		pub fn recent_window_stats() -> Vec<WindowStats<T::BlockNumber>> {
+			let max = T::MaxWindowStats::get().max(1);
+			let count = Self::window_stats_count();
+			(count.saturating_sub(max)..count).filter_map(|n| Self::window_stats(n % max)).collect()
+		}
+
+		fn add_to_aggregate(value: u32, to: T::BlockNumber, who: T::AccountId) -> DispatchResult {
+			/* Each finished block once */
+			ensure!(to > Self::last_aggregated_block(), Error::<T>::AlreadyAggregated);
+			ensure!(to < frame_system::Pallet::<T>::block_number(), Error::<T>::BlockNotFinished);
+
+			/* Add to aggregate */
+			let aggregate = Self::aggregate().checked_add(value).ok_or(Error::<T>::StorageOverflow)?;
+			<Aggregate<T>>::put(aggregate);
+			<LastAggregatedBlock<T>>::put(to);
+
+			Self::deposit_event(Event::AggregateSubmitted(value, to, aggregate, who));
+			Ok(())
+		}
+
+		/// Check `who` is the account of an authority key.
+		fn ensure_authority(who: &T::AccountId) -> DispatchResult {
+			let is_authority = Self::authorities().into_iter().any(|authority| authority.into_account() == *who);
+			ensure!(is_authority, Error::<T>::NotAuthority);
+			Ok(())
+		}
+
+		/// Whether the keystore holds an authority key.
+		fn local_authority_can_sign() -> bool {
+			Signer::<T, T::AuthorityId>::any_account()
+				.with_filter(Self::authorities().into_inner())
+				.can_sign()
+		}
+
+		/// Whether an unsigned aggregate sent at `block_number` can reach the chain.
+		fn unsigned_aggregate_due(block_number: T::BlockNumber) -> bool {
+			/* Pool would reject it as stale */
+			let next_unsigned_at = Self::next_unsigned_at();
+			if next_unsigned_at > block_number {
+				return false
+			}
+
+			/* Pool drops a second payload of the interval, wait while the last one may be in it */
+			match StorageValueRef::persistent(UNSIGNED_SENT_KEY).get::<(T::BlockNumber, T::BlockNumber)>() {
+				Ok(Some((sent_for, sent_at))) =>
+					sent_for != next_unsigned_at ||
+						block_number >= sent_at.saturating_add(T::UnsignedInterval::get()),
+				_ => true,
+			}
+		}
+
+		/// Submit value in an unsigned transaction with a payload signed by an authority key.
+		fn submit_aggregate_unsigned(
+			block_number: T::BlockNumber,
+			to: T::BlockNumber,
+			value: u32,
+		) -> Result<(), &'static str> {
+			let next_unsigned_at = Self::next_unsigned_at();
+			let result = Signer::<T, T::AuthorityId>::any_account()
+				.with_filter(Self::authorities().into_inner())
+				.send_unsigned_transaction(
+					|account| DataPayload { block_number, to, value, public: account.public.clone() },
+					|payload, signature| Call::submit_data_unsigned_with_signed_payload { payload, signature },
+				);
+
+			match result {
+				Some((_, Ok(()))) => {
+					StorageValueRef::persistent(UNSIGNED_SENT_KEY).set(&(next_unsigned_at, block_number));
+					Ok(())
+				},
+				Some((_, Err(()))) => Err("Failed to submit unsigned transaction"),
+				None => Err("No authority key signed the payload"),
+			}
+		}
+
+		/// Accept one unsigned submission per interval, from authorities, for past blocks only.
+		fn validate_transaction_parameters(
+			tag_prefix: &'static str,
+			next_unsigned_at: T::BlockNumber,
+			block_number: &T::BlockNumber,
+			public: &T::Public,
+		) -> TransactionValidity {
+			if &next_unsigned_at > block_number {
+				return InvalidTransaction::Stale.into()
+			}
+
+			let current_block = frame_system::Pallet::<T>::block_number();
+			if &current_block < block_number {
+				return InvalidTransaction::Future.into()
+			}
+
+			if !Self::authorities().contains(public) {
+				return InvalidTransaction::BadSigner.into()
+			}
+
+			ValidTransaction::with_tag_prefix(tag_prefix)
+				.priority(T::UnsignedPriority::get())
+				// One submission per interval, later ones replace or wait.
+				.and_provides(next_unsigned_at)
+				// Useless once the interval passed.
+				.longevity(T::UnsignedInterval::get().saturated_into::<u64>())
+				.propagate(true)
+				.build()
+		}
+
+		/// Send call in a transaction signed by an authority key in the keystore.
+		fn send_signed(call: Call<T>) -> Result<(), &'static str> {
+			let signer = Signer::<T, T::AuthorityId>::any_account().with_filter(Self::authorities().into_inner());
+			if !signer.can_sign() {
+				return Err("No local accounts available, insert a key with author_insertKey")
+			}
+
+			match signer.send_signed_transaction(|_account| call.clone()) {
+				Some((account, Ok(()))) => {
+					log::info!("[{:?}] Submitted signed transaction: {:?}", account.id, call);
+					Ok(())
+				},
+				Some((_, Err(()))) => Err("Failed to submit signed transaction"),
+				None => Err("No local account signed the transaction"),
+			}
+		}
+
+		/// Fetch current price in cents from the JSON endpoint at `url`.
+		fn fetch_price(url: &[u8]) -> Result<u32, http::Error> {
+			let url = sp_std::str::from_utf8(url).map_err(|_| http::Error::Unknown)?;
+
+			/* Give up on slow endpoints before the worker is killed */
+			let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(2_000));
+			let pending = http::Request::get(url)
+				.deadline(deadline)
+				.send()
+				.map_err(|_| http::Error::IoError)?;
+			let response = pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+
+			if response.code != 200 {
+				log::warn!("Unexpected price endpoint status code: {}", response.code);
+				return Err(http::Error::Unknown)
+			}
+
+			let body = response.body().collect::<Vec<u8>>();
+			Self::parse_price(&body).ok_or_else(|| {
+				log::warn!("Unable to parse price from {:?}", sp_std::str::from_utf8(&body));
+				http::Error::Unknown
 			})
 		}
+
+		/// Parse `{"USD": 6.23}` into cents, rounded.
+		pub(crate) fn parse_price(body: &[u8]) -> Option<u32> {
+			let response: PriceResponse = serde_json::from_slice(body).ok()?;
+			if !response.usd.is_finite() || response.usd < 0.0 {
+				return None
+			}
+
+			Some((response.usd * 100.0 + 0.5) as u32)
+		}
+
+		/// Average of the latest prices in cents, none before the first price.
+		pub fn average_price() -> Option<u32> {
+			let prices = Self::prices();
+			if prices.is_empty() {
+				return None
+			}
+
+			let sum = prices.iter().map(|price| *price as u64).sum::<u64>();
+			Some((sum / prices.len() as u64) as u32)
+		}
 	}
 }
@@ -1,20 +1,842 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	indexing::IndexedItem,
+	jobs::{Job, Outcome, Scheduler}, mock::*, DataPayload, Error, IndexingData, NumberStats,
+	StatsPayload, WindowStats, INDEXING_QUEUE,
+};
+use codec::Decode;
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use frame_system::offchain::SignedPayload;
+use sp_core::{
+	offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt},
+	sr25519,
+};
+use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
+use sp_runtime::{
+	offchain::{
+		storage::StorageValueRef,
+		storage_lock::{BlockAndTime, StorageLock},
+		Duration,
+	},
+	traits::ValidateUnsigned,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+	DispatchError,
+};
+use std::sync::Arc;
+
+const PHRASE: &str = "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
+
+// Externalities with offchain, pool and a keystore holding one worker key.
+fn offchain_test_ext() -> (
+	sp_io::TestExternalities,
+	sr25519::Public,
+	Arc<parking_lot::RwLock<testing::PoolState>>,
+	Arc<parking_lot::RwLock<testing::OffchainState>>,
+) {
+	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+	let public_key = SyncCryptoStore::sr25519_generate_new(
+		&keystore,
+		crate::KEY_TYPE,
+		Some(&format!("{}/hunter1", PHRASE)),
+	)
+	.unwrap();
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	t.register_extension(OffchainDbExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	t.register_extension(KeystoreExt(Arc::new(keystore)));
+
+	(t, public_key, pool_state, offchain_state)
+}
+
+// Write an item as `extrinsic` does, test externalities keep offchain indexing apart.
+fn index(block: u64, seq: u32, value: u32) {
+	let item = IndexedItem { seq, data: IndexingData(b"my_offchain_storage".to_vec(), value) };
+	StorageValueRef::persistent(&INDEXING_QUEUE.item_key(&block, seq)).set(&item);
+	StorageValueRef::persistent(&INDEXING_QUEUE.len_key(&block)).set(&(seq + 1));
+}
+
+// Unsigned aggregates the worker put in the pool, in order.
+fn pooled_unsigned_aggregates(
+	pool_state: &Arc<parking_lot::RwLock<testing::PoolState>>,
+) -> Vec<(DataPayload<sr25519::Public, u64>, sr25519::Signature)> {
+	pool_state
+		.read()
+		.transactions
+		.iter()
+		.filter_map(|tx| match Extrinsic::decode(&mut &**tx).unwrap().call {
+			Call::TemplateModule(crate::Call::submit_data_unsigned_with_signed_payload { payload, signature }) =>
+				Some((payload, signature)),
+			_ => None,
+		})
+		.collect()
+}
+
+// Values of the unsigned aggregates the worker put in the pool, in order.
+fn pooled_aggregate_values(pool_state: &Arc<parking_lot::RwLock<testing::PoolState>>) -> Vec<u32> {
+	pooled_unsigned_aggregates(pool_state).into_iter().map(|(payload, _)| payload.value).collect()
+}
+
+// Window statistics the worker put in the pool, in order.
+fn pooled_window_stats(pool_state: &Arc<parking_lot::RwLock<testing::PoolState>>) -> Vec<WindowStats<u64>> {
+	pool_state
+		.read()
+		.transactions
+		.iter()
+		.filter_map(|tx| match Extrinsic::decode(&mut &**tx).unwrap().call {
+			Call::TemplateModule(crate::Call::submit_window_stats_unsigned { payload, .. }) => Some(payload.window),
+			_ => None,
+		})
+		.collect()
+}
+
+fn done_job(_block_number: u64) -> Result<(), &'static str> {
+	Ok(())
+}
+
+fn failing_job(_block_number: u64) -> Result<(), &'static str> {
+	Err("no luck")
+}
+
+thread_local! {
+	static OFFCHAIN_STATE: std::cell::RefCell<Option<Arc<parking_lot::RwLock<testing::OffchainState>>>> =
+		std::cell::RefCell::new(None);
+}
+
+// Job taking 100ms of the test clock.
+fn slow_job(_block_number: u64) -> Result<(), &'static str> {
+	OFFCHAIN_STATE.with(|state| {
+		let state = state.borrow();
+		let mut state = state.as_ref().unwrap().write();
+		state.timestamp = state.timestamp.add(Duration::from_millis(100));
+	});
+	Ok(())
+}
 
 #[test]
-fn it_works_for_default_value() {
+fn increment_works() {
 	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
 		// Dispatch a signed extrinsic.
-		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::increment(Origin::signed(account(1)), 4));
+		assert_ok!(TemplateModule::increment(Origin::signed(account(1)), 6));
 		// Read pallet storage and assert an expected result.
-		assert_eq!(TemplateModule::something(), Some(42));
+		assert_eq!(TemplateModule::counter(account(1)), 10);
+		assert_eq!(TemplateModule::counter(account(2)), 0);
+		System::assert_last_event(crate::Event::CounterIncremented(account(1), 10).into());
+	});
+}
+
+#[test]
+fn increment_fails_above_max_value() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::increment(Origin::signed(account(1)), 8));
+		assert_noop!(
+			TemplateModule::increment(Origin::signed(account(1)), 3),
+			Error::<Test>::CounterAboveMax
+		);
+		assert_noop!(
+			TemplateModule::increment(Origin::signed(account(1)), u32::MAX),
+			Error::<Test>::CounterAboveMax
+		);
+	});
+}
+
+#[test]
+fn decrement_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::increment(Origin::signed(account(1)), 5));
+		assert_ok!(TemplateModule::decrement(Origin::signed(account(1)), 5));
+		assert_eq!(TemplateModule::counter(account(1)), 0);
+		System::assert_last_event(crate::Event::CounterDecremented(account(1), 0).into());
 	});
 }
 
 #[test]
-fn correct_error_for_none_value() {
+fn decrement_fails_below_zero() {
 	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::increment(Origin::signed(account(1)), 2));
+		assert_noop!(
+			TemplateModule::decrement(Origin::signed(account(1)), 3),
+			Error::<Test>::CounterBelowZero
+		);
+	});
+}
+
+#[test]
+fn reset_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::increment(Origin::signed(account(1)), 5));
+		assert_noop!(TemplateModule::reset(Origin::signed(account(1)), account(1)), DispatchError::BadOrigin);
+
+		assert_ok!(TemplateModule::reset(Origin::root(), account(1)));
+		assert_eq!(TemplateModule::counter(account(1)), 0);
+		System::assert_last_event(crate::Event::CounterReset(account(1)).into());
+
 		// Ensure the expected error is thrown when no value is present.
-		assert_noop!(TemplateModule::cause_error(Origin::signed(1)), Error::<Test>::NoneValue);
+		assert_noop!(TemplateModule::reset(Origin::root(), account(1)), Error::<Test>::CounterNotSet);
+	});
+}
+
+#[test]
+fn submit_aggregate_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		assert_ok!(TemplateModule::add_authority(Origin::root(), account(1)));
+		assert_ok!(TemplateModule::add_authority(Origin::root(), account(2)));
+		assert_ok!(TemplateModule::submit_aggregate(Origin::signed(account(1)), 3, 1));
+		assert_ok!(TemplateModule::submit_aggregate(Origin::signed(account(2)), 4, 2));
+		assert_eq!(TemplateModule::aggregate(), 7);
+		assert_eq!(TemplateModule::last_aggregated_block(), 2);
+		System::assert_last_event(crate::Event::AggregateSubmitted(4, 2, 7, account(2)).into());
+	});
+}
+
+#[test]
+fn submit_aggregate_counts_each_block_once() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		assert_ok!(TemplateModule::add_authority(Origin::root(), account(1)));
+		assert_ok!(TemplateModule::add_authority(Origin::root(), account(2)));
+		assert_ok!(TemplateModule::submit_aggregate(Origin::signed(account(1)), 3, 2));
+
+		// Another authority read the same indexed blocks.
+		assert_noop!(
+			TemplateModule::submit_aggregate(Origin::signed(account(2)), 3, 2),
+			Error::<Test>::AlreadyAggregated
+		);
+		assert_noop!(
+			TemplateModule::submit_aggregate(Origin::signed(account(2)), 3, 3),
+			Error::<Test>::BlockNotFinished
+		);
+		assert_eq!(TemplateModule::aggregate(), 3);
+	});
+}
+
+#[test]
+fn submit_aggregate_fails_on_overflow() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		assert_ok!(TemplateModule::add_authority(Origin::root(), account(1)));
+		assert_ok!(TemplateModule::submit_aggregate(Origin::signed(account(1)), u32::MAX, 1));
+		assert_noop!(
+			TemplateModule::submit_aggregate(Origin::signed(account(1)), 1, 2),
+			Error::<Test>::StorageOverflow
+		);
+	});
+}
+
+#[test]
+fn submit_aggregate_requires_authority() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		assert_ok!(TemplateModule::add_authority(Origin::root(), account(1)));
+		assert_noop!(TemplateModule::submit_aggregate(Origin::signed(account(2)), 3, 1), Error::<Test>::NotAuthority);
+		assert_eq!(TemplateModule::aggregate(), 0);
+	});
+}
+
+#[test]
+fn offchain_worker_skips_without_keys() {
+	let (offchain, _offchain_state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	t.register_extension(OffchainDbExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	t.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
+
+	t.execute_with(|| {
+		index(1, 0, 7);
+
+		TemplateModule::offchain_worker(2);
+
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn extrinsic_indexes_items_per_extrinsic() {
+	let mut t = new_test_ext();
+	t.execute_with(|| {
+		System::set_block_number(1);
+		System::set_extrinsic_index(1);
+		assert_ok!(TemplateModule::extrinsic(Origin::signed(account(1)), 7));
+		System::set_extrinsic_index(2);
+		assert_ok!(TemplateModule::extrinsic(Origin::signed(account(1)), 8));
+	});
+
+	// Make the offchain indexing of the block visible to the worker.
+	t.persist_offchain_overlay();
+	let (offchain, _offchain_state) = testing::TestOffchainExt::with_offchain_db(t.offchain_db());
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
+	t.register_extension(OffchainWorkerExt::new(offchain));
+
+	t.execute_with(|| {
+		let items = INDEXING_QUEUE.items::<_, IndexingData>(&1u64);
+		assert_eq!(items.iter().map(|item| (item.seq, item.data.1)).collect::<Vec<_>>(), vec![(1, 7), (2, 8)]);
+	});
+}
+
+#[test]
+fn offchain_worker_submits_items_of_a_block_together() {
+	let (mut t, public_key, pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		index(1, 0, 7);
+		index(1, 1, 8);
+
+		TemplateModule::offchain_worker(2);
+
+		assert_eq!(pooled_aggregate_values(&pool_state), vec![15]);
+	});
+}
+
+#[test]
+fn offchain_worker_skips_genesis() {
+	let (mut t, _public_key, pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		TemplateModule::offchain_worker(0);
+
+		assert!(pool_state.read().transactions.is_empty());
+		assert_eq!(StorageValueRef::persistent(crate::LAST_PROCESSED_KEY).get::<u64>(), Ok(None));
+	});
+}
+
+#[test]
+fn offchain_worker_processes_indexed_block_once() {
+	let (mut t, public_key, pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		index(1, 0, 7);
+
+		TemplateModule::offchain_worker(2);
+		assert_eq!(pooled_aggregate_values(&pool_state), vec![7]);
+		assert_eq!(StorageValueRef::persistent(crate::LAST_PROCESSED_KEY).get::<u64>(), Ok(Some(1)));
+
+		// Same block imported again, or a lower block from another fork.
+		TemplateModule::offchain_worker(2);
+		TemplateModule::offchain_worker(1);
+		assert_eq!(pooled_aggregate_values(&pool_state), vec![7]);
+	});
+}
+
+#[test]
+fn offchain_worker_catches_up_on_missed_blocks() {
+	let (mut t, public_key, pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		TemplateModule::offchain_worker(2);
+		assert!(pooled_aggregate_values(&pool_state).is_empty());
+
+		for (block, value) in [(2u64, 3u32), (3, 4)] {
+			index(block, 0, value);
+		}
+
+		// Worker did not run for block 3, both indexed blocks are processed at block 4.
+		TemplateModule::offchain_worker(4);
+
+		assert_eq!(pooled_aggregate_values(&pool_state), vec![7]);
+		assert_eq!(StorageValueRef::persistent(crate::LAST_PROCESSED_KEY).get::<u64>(), Ok(Some(3)));
+	});
+}
+
+#[test]
+fn offchain_worker_waits_for_lock() {
+	let (mut t, public_key, pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		index(1, 0, 7);
+
+		let mut lock = StorageLock::<BlockAndTime<System>>::with_block_and_time_deadline(
+			crate::LOCK_KEY,
+			3,
+			Duration::from_millis(4_000),
+		);
+		let guard = lock.try_lock().unwrap();
+
+		// Another worker holds the lock, block stays unprocessed.
+		TemplateModule::offchain_worker(2);
+		assert!(pool_state.read().transactions.is_empty());
+		assert_eq!(StorageValueRef::persistent(crate::LAST_PROCESSED_KEY).get::<u64>(), Ok(None));
+
+		drop(guard);
+		TemplateModule::offchain_worker(2);
+		assert_eq!(pooled_aggregate_values(&pool_state), vec![7]);
+	});
+}
+
+#[test]
+fn add_authority_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(TemplateModule::add_authority(Origin::signed(account(1)), account(1)), DispatchError::BadOrigin);
+		assert_ok!(TemplateModule::add_authority(Origin::root(), account(1)));
+		assert_noop!(TemplateModule::add_authority(Origin::root(), account(1)), Error::<Test>::AlreadyAuthority);
+		assert_ok!(TemplateModule::add_authority(Origin::root(), account(2)));
+		assert_noop!(TemplateModule::add_authority(Origin::root(), account(3)), Error::<Test>::TooManyAuthorities);
+
+		assert_ok!(TemplateModule::remove_authority(Origin::root(), account(1)));
+		assert_eq!(TemplateModule::authorities().into_inner(), vec![account(2)]);
+		assert_noop!(TemplateModule::remove_authority(Origin::root(), account(1)), Error::<Test>::NotAuthority);
+	});
+}
+
+#[test]
+fn offchain_worker_submits_unsigned_with_signed_payload() {
+	let (mut t, public_key, pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		index(1, 0, 7);
+
+		TemplateModule::offchain_worker(2);
+
+		// Window statistics follow the aggregate.
+		let tx = pool_state.write().transactions.remove(0);
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, None);
+		if let Call::TemplateModule(crate::Call::submit_data_unsigned_with_signed_payload { payload, signature }) = tx.call {
+			assert_eq!(payload, DataPayload { block_number: 2, to: 1, value: 7, public: public_key });
+			assert!(<DataPayload<_, _> as SignedPayload<Test>>::verify::<crate::crypto::TestAuthId>(&payload, signature));
+		} else {
+			panic!("unexpected call: {:?}", tx.call);
+		}
+	});
+}
+
+#[test]
+fn offchain_worker_submits_unsigned_sum_of_indexed_items() {
+	let (mut t, public_key, pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		System::set_block_number(2);
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		index(1, 0, 7);
+		index(1, 1, 8);
+
+		TemplateModule::offchain_worker(2);
+
+		let aggregates = pooled_unsigned_aggregates(&pool_state);
+		assert_eq!(aggregates.len(), 1);
+		let (payload, signature) = aggregates[0].clone();
+		assert_eq!(payload.value, 15);
+		assert!(INDEXING_QUEUE.items::<_, IndexingData>(&1u64).is_empty());
+
+		// Items stay queued until the chain covers their block.
+		assert_eq!(INDEXING_QUEUE.items::<_, IndexingData>(&1u64).len(), 2);
+
+		// Payload of the interval may still be in the pool, next items wait for the next interval.
+		index(2, 0, 5);
+		TemplateModule::offchain_worker(3);
+		assert_eq!(pooled_aggregate_values(&pool_state), vec![15]);
+		assert!(pool_state
+			.read()
+			.transactions
+			.iter()
+			.all(|tx| Extrinsic::decode(&mut &**tx).unwrap().signature.is_none()));
+
+		let call = crate::Call::submit_data_unsigned_with_signed_payload { payload: payload.clone(), signature: signature.clone() };
+		assert_ok!(TemplateModule::validate_unsigned(TransactionSource::External, &call));
+		assert_ok!(TemplateModule::submit_data_unsigned_with_signed_payload(Origin::none(), payload, signature));
+		assert_eq!(TemplateModule::aggregate(), 15);
+		assert_eq!(TemplateModule::last_aggregated_block(), 1);
+
+		// Next interval clears the applied block and sends the rest.
+		TemplateModule::offchain_worker(7);
+		assert!(INDEXING_QUEUE.items::<_, IndexingData>(&1u64).is_empty());
+		assert_eq!(pooled_aggregate_values(&pool_state), vec![15, 5]);
+		let (payload, _) = pooled_unsigned_aggregates(&pool_state)[1].clone();
+		assert_eq!(payload.to, 6);
+	});
+}
+
+#[test]
+fn offchain_worker_resends_dropped_aggregate() {
+	let (mut t, public_key, pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		index(1, 0, 7);
+
+		TemplateModule::offchain_worker(2);
+		assert_eq!(pooled_aggregate_values(&pool_state), vec![7]);
+
+		// Pool drops the payload before it is included.
+		pool_state.write().transactions.clear();
+		for block in 3..7 {
+			TemplateModule::offchain_worker(block);
+		}
+		assert!(pooled_aggregate_values(&pool_state).is_empty());
+
+		TemplateModule::offchain_worker(7);
+		assert_eq!(pooled_aggregate_values(&pool_state), vec![7]);
+	});
+}
+
+#[test]
+fn number_stats_merge_works() {
+	let mut stats = NumberStats::default();
+	assert_eq!(stats.mean(), None);
+
+	stats.add(7);
+	stats.add(3);
+	assert_eq!(stats, NumberStats { count: 2, sum: 10, min: 3, max: 7 });
+
+	stats.merge(&NumberStats::default());
+	stats.merge(&NumberStats { count: 1, sum: 11, min: 11, max: 11 });
+	assert_eq!(stats, NumberStats { count: 3, sum: 21, min: 3, max: 11 });
+	assert_eq!(stats.mean(), Some(7));
+}
+
+#[test]
+fn offchain_worker_submits_window_stats() {
+	let (mut t, public_key, pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		index(1, 0, 7);
+		index(1, 1, 3);
+
+		TemplateModule::offchain_worker(2);
+		assert_eq!(
+			pooled_window_stats(&pool_state),
+			vec![WindowStats { from: 0, to: 1, stats: NumberStats { count: 2, sum: 10, min: 3, max: 7 } }]
+		);
+
+		// Window of three blocks slides past block 1.
+		index(2, 0, 5);
+		index(4, 0, 9);
+		TemplateModule::offchain_worker(5);
+		assert_eq!(
+			pooled_window_stats(&pool_state)[1],
+			WindowStats { from: 2, to: 4, stats: NumberStats { count: 2, sum: 14, min: 5, max: 9 } }
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_skips_without_authority() {
+	let (mut t, _public_key, pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		index(1, 0, 7);
+
+		TemplateModule::offchain_worker(2);
+
+		// Worker keys outside the authority set submit nothing.
+		assert!(pool_state.read().transactions.is_empty());
+
+		// Job is done, not failed, on nodes that are not authorities.
+		let aggregate = &TemplateModule::jobs()[0];
+		assert_eq!(aggregate.name, "aggregate");
+		assert_eq!((aggregate.run)(3), Ok(()));
+		assert_eq!(StorageValueRef::persistent(crate::LAST_PROCESSED_KEY).get::<u64>(), Ok(None));
+	});
+}
+
+#[test]
+fn submit_window_stats_keeps_ring_buffer() {
+	let (mut t, public_key, _pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		System::set_block_number(5);
+		let windows: Vec<_> = (1..=3u64)
+			.map(|to| {
+				let stats = NumberStats { count: 1, sum: to, min: to as u32, max: to as u32 };
+				WindowStats { from: to, to, stats }
+			})
+			.collect();
+
+		for window in windows.iter().cloned() {
+			let payload = StatsPayload { block_number: 5, window, public: public_key };
+			let signature = <StatsPayload<_, _> as SignedPayload<Test>>::sign::<crate::crypto::TestAuthId>(&payload).unwrap();
+			assert_ok!(TemplateModule::submit_window_stats_unsigned(Origin::none(), payload, signature));
+		}
+
+		System::assert_last_event(crate::Event::WindowStatsSubmitted(windows[2].clone()).into());
+		assert_eq!(TemplateModule::window_stats_count(), 3);
+		assert_eq!(TemplateModule::next_stats_at(), 10);
+		// Oldest window overwritten.
+		assert_eq!(TemplateModule::window_stats(0), Some(windows[2].clone()));
+		assert_eq!(TemplateModule::recent_window_stats(), windows[1..].to_vec());
+	});
+}
+
+#[test]
+fn validate_unsigned_checks_window_stats() {
+	let (mut t, public_key, _pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		System::set_block_number(2);
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		let call = |to: u64| {
+			let window = WindowStats { from: 0, to, stats: NumberStats { count: 1, sum: 7, min: 7, max: 7 } };
+			let payload = StatsPayload { block_number: 2, window, public: public_key };
+			let signature = <StatsPayload<_, _> as SignedPayload<Test>>::sign::<crate::crypto::TestAuthId>(&payload).unwrap();
+			crate::Call::submit_window_stats_unsigned { payload, signature }
+		};
+
+		assert_ok!(TemplateModule::validate_unsigned(TransactionSource::External, &call(1)));
+
+		// Window must end before the submission block.
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call(2)),
+			InvalidTransaction::Call.into()
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_works() {
+	let (mut t, public_key, _pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		System::set_block_number(2);
+		let payload = DataPayload { block_number: 2, to: 1, value: 7, public: public_key };
+		let signature = <DataPayload<_, _> as SignedPayload<Test>>::sign::<crate::crypto::TestAuthId>(&payload).unwrap();
+		let call = crate::Call::submit_data_unsigned_with_signed_payload { payload: payload.clone(), signature: signature.clone() };
+
+		// Not an authority yet.
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadSigner.into()
+		);
+
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		let valid = TemplateModule::validate_unsigned(TransactionSource::External, &call).unwrap();
+		assert_eq!(valid.priority, 1 << 20);
+		assert_eq!(valid.longevity, 5);
+
+		// Signature must match payload.
+		let forged = crate::Call::submit_data_unsigned_with_signed_payload {
+			payload: DataPayload { value: 8, ..payload.clone() },
+			signature: signature.clone(),
+		};
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &forged),
+			InvalidTransaction::BadProof.into()
+		);
+
+		// One submission per interval.
+		assert_ok!(TemplateModule::submit_data_unsigned_with_signed_payload(Origin::none(), payload, signature.clone()));
+		assert_eq!(TemplateModule::aggregate(), 7);
+		assert_eq!(TemplateModule::next_unsigned_at(), 7);
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Stale.into()
+		);
+
+		// Blocks already aggregated, even once the interval passed.
+		System::set_block_number(7);
+		let payload = DataPayload { block_number: 7, to: 1, value: 7, public: public_key };
+		let signature = <DataPayload<_, _> as SignedPayload<Test>>::sign::<crate::crypto::TestAuthId>(&payload).unwrap();
+		let call = crate::Call::submit_data_unsigned_with_signed_payload { payload, signature };
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Stale.into()
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_future_payload() {
+	let (mut t, public_key, _pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		System::set_block_number(2);
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		let payload = DataPayload { block_number: 3, to: 1, value: 7, public: public_key };
+		let signature = <DataPayload<_, _> as SignedPayload<Test>>::sign::<crate::crypto::TestAuthId>(&payload).unwrap();
+		let call = crate::Call::submit_data_unsigned_with_signed_payload { payload, signature };
+
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Future.into()
+		);
+	});
+}
+
+#[test]
+fn submit_price_keeps_moving_average() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::add_authority(Origin::root(), account(1)));
+		assert_eq!(TemplateModule::average_price(), None);
+
+		for price in [100, 200, 300, 600] {
+			assert_ok!(TemplateModule::submit_price(Origin::signed(account(1)), price));
+		}
+
+		// Oldest price dropped.
+		assert_eq!(TemplateModule::prices().into_inner(), vec![200, 300, 600]);
+		assert_eq!(TemplateModule::average_price(), Some(366));
+		System::assert_last_event(crate::Event::PriceSubmitted(600, account(1)).into());
+	});
+}
+
+#[test]
+fn submit_price_requires_authority() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::add_authority(Origin::root(), account(1)));
+		assert_noop!(TemplateModule::submit_price(Origin::signed(account(2)), 100), Error::<Test>::NotAuthority);
+		assert!(TemplateModule::prices().is_empty());
+	});
+}
+
+#[test]
+fn parse_price_works() {
+	assert_eq!(TemplateModule::parse_price(br#"{"USD": 6.23}"#), Some(623));
+	assert_eq!(TemplateModule::parse_price(br#"{"USD": 0.5, "EUR": 0.4}"#), Some(50));
+	assert_eq!(TemplateModule::parse_price(br#"{"USD": -1}"#), None);
+	assert_eq!(TemplateModule::parse_price(br#"{"EUR": 6.23}"#), None);
+	assert_eq!(TemplateModule::parse_price(b"not json"), None);
+}
+
+#[test]
+fn offchain_worker_fetches_and_submits_price() {
+	const URL: &str = "https://prices.example.com/data/price?fsym=DOT&tsyms=USD";
+
+	let (mut t, public_key, pool_state, offchain_state) = offchain_test_ext();
+
+	offchain_state.write().persistent_storage.set(b"", crate::PRICE_URL_KEY, URL.as_bytes());
+	offchain_state.write().expect_request(testing::PendingRequest {
+		method: "GET".into(),
+		uri: URL.into(),
+		response: Some(br#"{"USD": 6.23}"#.to_vec()),
+		sent: true,
+		..Default::default()
+	});
+
+	t.execute_with(|| {
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		TemplateModule::offchain_worker(2);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature.unwrap().0, 0);
+		assert_eq!(tx.call, Call::TemplateModule(crate::Call::submit_price { price: 623 }));
+	});
+}
+
+#[test]
+fn offchain_worker_skips_price_on_bad_response() {
+	const URL: &str = "https://prices.example.com/data/price?fsym=DOT&tsyms=USD";
+
+	let (mut t, public_key, pool_state, offchain_state) = offchain_test_ext();
+
+	offchain_state.write().persistent_storage.set(b"", crate::PRICE_URL_KEY, URL.as_bytes());
+	offchain_state.write().expect_request(testing::PendingRequest {
+		method: "GET".into(),
+		uri: URL.into(),
+		response: Some(b"Service Unavailable".to_vec()),
+		sent: true,
+		..Default::default()
+	});
+
+	t.execute_with(|| {
+		assert_ok!(TemplateModule::add_authority(Origin::root(), public_key));
+		TemplateModule::offchain_worker(2);
+
+		assert!(!pool_state.read().transactions.iter().any(|tx| matches!(
+			Extrinsic::decode(&mut &**tx).unwrap().call,
+			Call::TemplateModule(crate::Call::submit_price { .. })
+		)));
+	});
+}
+
+#[test]
+fn offchain_worker_skips_price_without_authority() {
+	const URL: &str = "https://prices.example.com/data/price?fsym=DOT&tsyms=USD";
+
+	// No request is expected, the test extension panics on unexpected ones.
+	let (mut t, _public_key, pool_state, offchain_state) = offchain_test_ext();
+	offchain_state.write().persistent_storage.set(b"", crate::PRICE_URL_KEY, URL.as_bytes());
+
+	t.execute_with(|| {
+		TemplateModule::offchain_worker(2);
+
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn scheduler_runs_jobs_every_period() {
+	let (mut t, _public_key, _pool_state, _offchain_state) = offchain_test_ext();
+	let scheduler = Scheduler::new(b"test::jobs::", 1_000);
+	let jobs = [
+		Job { name: "done", period: 3, run: done_job },
+		Job { name: "failing", period: 3, run: failing_job },
+	];
+
+	t.execute_with(|| {
+		assert_eq!(
+			scheduler.run(&jobs, 1),
+			vec![("done", Outcome::Done), ("failing", Outcome::Failed("no luck"))]
+		);
+		assert_eq!(StorageValueRef::persistent(&scheduler.last_run_key("done")).get::<u64>(), Ok(Some(1)));
+
+		// Failed jobs retry on the next block.
+		assert_eq!(
+			scheduler.run(&jobs, 2),
+			vec![("done", Outcome::NotDue), ("failing", Outcome::Failed("no luck"))]
+		);
+		assert_eq!(
+			scheduler.run(&jobs, 4),
+			vec![("done", Outcome::Done), ("failing", Outcome::Failed("no luck"))]
+		);
+	});
+}
+
+#[test]
+fn scheduler_stops_when_over_budget() {
+	let (mut t, _public_key, _pool_state, offchain_state) = offchain_test_ext();
+	OFFCHAIN_STATE.with(|state| *state.borrow_mut() = Some(offchain_state));
+	let scheduler = Scheduler::new(b"test::jobs::", 50);
+	let jobs = [
+		Job { name: "slow", period: 1, run: slow_job },
+		Job { name: "done", period: 1, run: done_job },
+	];
+
+	t.execute_with(|| {
+		assert_eq!(scheduler.run(&jobs, 1), vec![("slow", Outcome::Done), ("done", Outcome::OverBudget)]);
+
+		// Left over jobs run first thing on the next block.
+		assert_eq!(StorageValueRef::persistent(&scheduler.last_run_key("done")).get::<u64>(), Ok(None));
+		let jobs = [Job { name: "done", period: 1, run: done_job }];
+		assert_eq!(scheduler.run(&jobs, 2), vec![("done", Outcome::Done)]);
+	});
+}
+
+#[test]
+fn cleanup_removes_old_unaggregated_items() {
+	let (mut t, _public_key, _pool_state, _offchain_state) = offchain_test_ext();
+
+	t.execute_with(|| {
+		index(1, 0, 7);
+		index(1, 1, 8);
+		index(2, 0, 9);
+
+		// Nothing processed long enough ago.
+		StorageValueRef::persistent(crate::LAST_PROCESSED_KEY).set(&(crate::INDEXING_RETENTION as u64));
+		assert_ok!(TemplateModule::cleanup_indexed(0));
+		assert_eq!(StorageValueRef::persistent(&INDEXING_QUEUE.len_key(&1u64)).get::<u32>(), Ok(Some(2)));
+
+		StorageValueRef::persistent(crate::LAST_PROCESSED_KEY).set(&(crate::INDEXING_RETENTION as u64 + 1));
+		assert_ok!(TemplateModule::cleanup_indexed(0));
+		let item_key = INDEXING_QUEUE.item_key(&1u64, 0);
+		assert!(sp_io::offchain::local_storage_get(sp_core::offchain::StorageKind::PERSISTENT, &item_key).is_none());
+		assert_eq!(StorageValueRef::persistent(&INDEXING_QUEUE.len_key(&1u64)).get::<u32>(), Ok(None));
+		assert_eq!(StorageValueRef::persistent(&INDEXING_QUEUE.len_key(&2u64)).get::<u32>(), Ok(Some(1)));
+		assert_eq!(StorageValueRef::persistent(crate::CLEANED_UP_TO_KEY).get::<u64>(), Ok(Some(1)));
 	});
 }
@@ -0,0 +1,190 @@
+//! Weights for pallet_template
+//!
+//! PLACEHOLDER, not benchmark output. Storage access is counted by hand from the benchmarks in
+//! `benchmarking.rs` and the base weights are rough estimates. This tree has no node or runtime
+//! to run the benchmarks with, so the file still has to be regenerated on reference hardware from
+//! a node built with `--features runtime-benchmarks` that includes the pallet:
+//!
+//! ```text
+//! ./target/release/node-template benchmark pallet \
+//!     --chain dev \
+//!     --pallet pallet_template \
+//!     --extrinsic '*' \
+//!     --steps 50 \
+//!     --repeat 20 \
+//!     --execution wasm \
+//!     --wasm-execution compiled \
+//!     --output pallets/template/src/weights.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_template.
+pub trait WeightInfo {
+	fn increment() -> Weight;
+	fn decrement() -> Weight;
+	fn reset() -> Weight;
+	fn extrinsic() -> Weight;
+	fn submit_aggregate() -> Weight;
+	fn submit_data_unsigned_with_signed_payload() -> Weight;
+	fn submit_window_stats_unsigned() -> Weight;
+	fn submit_price() -> Weight;
+	fn add_authority() -> Weight;
+	fn remove_authority() -> Weight;
+}
+
+/// Placeholder weights for pallet_template, see the module documentation.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: TemplateModule Counters (r:1 w:1)
+	// Base weight is a placeholder guess, not measured
+	fn increment() -> Weight {
+		(16_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Counters (r:1 w:1)
+	// Base weight is a placeholder guess, not measured
+	fn decrement() -> Weight {
+		(16_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Counters (r:1 w:1)
+	// Base weight is a placeholder guess, not measured
+	fn reset() -> Weight {
+		(14_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: unknown [0x3a65787472696e7369635f696e646578] (r:1 w:0)
+	// Offchain index: item and length of the queue (r:0 w:2)
+	fn extrinsic() -> Weight {
+		(13_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: TemplateModule Authorities (r:1 w:0)
+	// Storage: TemplateModule LastAggregatedBlock (r:1 w:1)
+	// Storage: TemplateModule Aggregate (r:1 w:1)
+	fn submit_aggregate() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: TemplateModule LastAggregatedBlock (r:1 w:1)
+	// Storage: TemplateModule Aggregate (r:1 w:1)
+	// Storage: TemplateModule NextUnsignedAt (r:0 w:1)
+	fn submit_data_unsigned_with_signed_payload() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: TemplateModule WindowStatsCount (r:1 w:1)
+	// Storage: TemplateModule NextStatsAt (r:0 w:1)
+	// Storage: TemplateModule WindowStatsRing (r:0 w:1)
+	fn submit_window_stats_unsigned() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: TemplateModule Authorities (r:1 w:0)
+	// Storage: TemplateModule Prices (r:1 w:1)
+	fn submit_price() -> Weight {
+		(16_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Authorities (r:1 w:1)
+	fn add_authority() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Authorities (r:1 w:1)
+	fn remove_authority() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: TemplateModule Counters (r:1 w:1)
+	// Base weight is a placeholder guess, not measured
+	fn increment() -> Weight {
+		(16_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Counters (r:1 w:1)
+	// Base weight is a placeholder guess, not measured
+	fn decrement() -> Weight {
+		(16_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Counters (r:1 w:1)
+	// Base weight is a placeholder guess, not measured
+	fn reset() -> Weight {
+		(14_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: unknown [0x3a65787472696e7369635f696e646578] (r:1 w:0)
+	// Offchain index: item and length of the queue (r:0 w:2)
+	fn extrinsic() -> Weight {
+		(13_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: TemplateModule Authorities (r:1 w:0)
+	// Storage: TemplateModule LastAggregatedBlock (r:1 w:1)
+	// Storage: TemplateModule Aggregate (r:1 w:1)
+	fn submit_aggregate() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: TemplateModule LastAggregatedBlock (r:1 w:1)
+	// Storage: TemplateModule Aggregate (r:1 w:1)
+	// Storage: TemplateModule NextUnsignedAt (r:0 w:1)
+	fn submit_data_unsigned_with_signed_payload() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: TemplateModule WindowStatsCount (r:1 w:1)
+	// Storage: TemplateModule NextStatsAt (r:0 w:1)
+	// Storage: TemplateModule WindowStatsRing (r:0 w:1)
+	fn submit_window_stats_unsigned() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: TemplateModule Authorities (r:1 w:0)
+	// Storage: TemplateModule Prices (r:1 w:1)
+	fn submit_price() -> Weight {
+		(16_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Authorities (r:1 w:1)
+	fn add_authority() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Authorities (r:1 w:1)
+	fn remove_authority() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+}